@@ -0,0 +1,322 @@
+//! A minimal allocator interface for allocating records in custom memory
+//! regions such as arenas or memory pools.
+//!
+//! Records created through e.g. [`Owned::new_in`][crate::Owned::new_in] or
+//! [`Atomic::new_in`][crate::Atomic::new_in] store their allocator alongside
+//! themselves, so they are always de-allocated with the correct allocator,
+//! regardless of whether they are dropped directly or retired and later
+//! reclaimed by a reclamation scheme.
+//!
+//! With the `pool` feature enabled, the [`Global`] allocator recycles the
+//! memory of reclaimed records through bounded thread local free lists and a
+//! global overflow list, so that records of the same type and layout can be
+//! re-used by later allocations without a round-trip through the global
+//! memory allocator.
+
+#[cfg(feature = "pool")]
+mod pool;
+
+#[cfg(not(feature = "std"))]
+use alloc::alloc::{alloc, dealloc, handle_alloc_error};
+#[cfg(feature = "std")]
+use std::alloc::handle_alloc_error;
+#[cfg(all(feature = "std", not(feature = "pool")))]
+use std::alloc::{alloc, dealloc};
+
+use core::alloc::Layout;
+use core::cmp;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+use crate::{Reclaim, Record};
+
+/// The type-erased function for releasing the memory block of the record at
+/// the given address.
+pub(crate) type ReleaseFn = unsafe fn(NonNull<()>, Release);
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Allocator (trait)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A trait for allocators that records can be allocated with.
+///
+/// # Safety
+///
+/// Memory returned by [`allocate`][Allocator::allocate] must be valid for
+/// reads and writes of the requested size and alignment until it is passed to
+/// [`deallocate`][Allocator::deallocate].
+/// Since records may be reclaimed by any thread, memory allocated by one
+/// thread must be de-allocatable by every other thread.
+pub unsafe trait Allocator {
+    /// Allocates a block of memory fitting the given (non-zero sized)
+    /// `layout` or returns `None`, if the allocation fails.
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// De-allocates the memory at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// The memory must have been allocated by this allocator with the same
+    /// `layout` and must not be de-allocated more than once.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+/********** blanket impl **************************************************************************/
+
+unsafe impl<A: Allocator> Allocator for &A {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        (**self).allocate(layout)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global memory allocator, which is used for all records that are not
+/// explicitly allocated with a different allocator.
+///
+/// If the `pool` feature is enabled, freed memory is cached for later re-use
+/// instead of being immediately returned to the global memory allocator.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Global;
+
+/********** impl Allocator ************************************************************************/
+
+unsafe impl Allocator for Global {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        #[cfg(feature = "pool")]
+        return pool::allocate(layout);
+        #[cfg(not(feature = "pool"))]
+        return NonNull::new(unsafe { alloc(layout) });
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        #[cfg(feature = "pool")]
+        pool::deallocate(ptr, layout);
+        #[cfg(not(feature = "pool"))]
+        dealloc(ptr.as_ptr(), layout);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Allocation
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The memory block allocated for every record, which stores the allocator
+/// behind the record itself.
+///
+/// Every memory block is prefixed with the [`ReleaseFn`] for the record,
+/// which is always located immediately in front of the record, so it can be
+/// determined from a type-erased record pointer alone.
+#[repr(C)]
+pub(crate) struct Allocation<T, R: Reclaim, A> {
+    record: Record<T, R>,
+    alloc: A,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: Reclaim, A: Allocator + Send + 'static> Allocation<T, R, A> {
+    /// Allocates a memory block with the given `alloc`, moves the `record`
+    /// into it and returns a pointer to the record.
+    ///
+    /// # Panics
+    ///
+    /// Aborts the process if the allocation fails.
+    #[inline]
+    pub fn alloc(record: Record<T, R>, alloc: A) -> NonNull<Record<T, R>> {
+        // the layout is never zero-sized, since it contains the release function pointer
+        let (layout, offset) = prefixed(Layout::new::<Self>());
+        let ptr = match alloc.allocate(layout) {
+            Some(ptr) => ptr,
+            None => handle_alloc_error(layout),
+        };
+
+        unsafe {
+            let ptr = NonNull::new_unchecked(ptr.as_ptr().add(offset)).cast::<Self>();
+            ptr.as_ptr().write(Self { record, alloc });
+            write_release_fn(ptr.cast(), Self::release);
+            ptr.cast()
+        }
+    }
+
+    /// Releases the memory block of the record at `ptr` with its stored
+    /// allocator.
+    #[inline]
+    unsafe fn release(ptr: NonNull<()>, release: Release) {
+        let allocation = ptr.cast::<Self>().as_ptr();
+        release.drop_record(&mut (*allocation).record);
+
+        let alloc = ptr::read(&(*allocation).alloc);
+        let (layout, offset) = prefixed(Layout::new::<Self>());
+        alloc.deallocate(NonNull::new_unchecked((ptr.as_ptr() as *mut u8).sub(offset)), layout);
+    }
+}
+
+impl<T, R: Reclaim> Allocation<T, R, ()> {
+    /// Drops the record at `record` in place and de-allocates its memory
+    /// block with the allocator it was allocated with.
+    ///
+    /// # Safety
+    ///
+    /// The `record` must have been allocated with [`Allocation::alloc`] and
+    /// must not be used afterwards.
+    #[inline]
+    pub unsafe fn free(record: NonNull<Record<T, R>>) {
+        release(record.cast(), Release::Drop);
+    }
+
+    /// Reclaims the retired record at `record`, i.e. drops it in place and
+    /// de-allocates its memory block with the allocator it was allocated
+    /// with.
+    ///
+    /// # Safety
+    ///
+    /// The `record` must have been allocated with [`Allocation::alloc`] and
+    /// must not be used afterwards.
+    #[inline]
+    pub unsafe fn reclaim(record: NonNull<Record<T, R>>) {
+        release(record.cast(), Release::Reclaim);
+    }
+
+    /// Moves the element out of the record at `record` and de-allocates its
+    /// memory block with the allocator it was allocated with.
+    ///
+    /// # Safety
+    ///
+    /// The `record` must have been allocated with [`Allocation::alloc`] and
+    /// must not be used afterwards.
+    #[inline]
+    pub unsafe fn into_elem(record: NonNull<Record<T, R>>) -> T {
+        let Record { header, elem } = ptr::read(record.as_ptr());
+        drop(header);
+        release(record.cast(), Release::Dealloc);
+        elem
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Release
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The ways of releasing the memory block of a record.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Release {
+    /// The record is dropped before its memory is de-allocated.
+    Drop,
+    /// The retired record is reclaimed, i.e. it is dropped after calling the
+    /// [`on_reclaim`][Reclaim::on_reclaim] hook of its reclamation scheme and
+    /// its memory is de-allocated.
+    Reclaim,
+    /// The record has already been moved out, so its memory is only
+    /// de-allocated.
+    Dealloc,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Release {
+    /// Drops the `record` in place, unless only its memory is to be
+    /// de-allocated.
+    #[inline]
+    pub unsafe fn drop_record<T, R: Reclaim>(self, record: *mut Record<T, R>) {
+        match self {
+            Release::Drop => ptr::drop_in_place(record),
+            Release::Reclaim => {
+                R::on_reclaim(NonNull::from(&(*record).elem));
+                ptr::drop_in_place(record);
+            }
+            Release::Dealloc => {}
+        }
+    }
+}
+
+/********** helper functions **********************************************************************/
+
+/// Releases the memory block of the type-erased record at `record` with the
+/// [`ReleaseFn`] stored in front of it.
+///
+/// # Safety
+///
+/// The `record` must have been allocated with [`Allocation::alloc`] (or as an
+/// unsized record) and must not be used afterwards.
+#[inline]
+pub(crate) unsafe fn release(record: NonNull<()>, release: Release) {
+    let release_fn = (record.as_ptr() as *const ReleaseFn).sub(1).read();
+    release_fn(record, release);
+}
+
+/// Stores the `release` function immediately in front of the record at
+/// `record`.
+#[inline]
+pub(crate) unsafe fn write_release_fn(record: NonNull<()>, release: ReleaseFn) {
+    (record.as_ptr() as *mut ReleaseFn).sub(1).write(release);
+}
+
+/// Returns the layout of a memory block for a value with the given `layout`
+/// that is prefixed with a [`ReleaseFn`] and the offset of the value within
+/// the block.
+///
+/// The offset is chosen such that the [`ReleaseFn`] is located immediately in
+/// front of the value.
+#[inline]
+pub(crate) fn prefixed(layout: Layout) -> (Layout, usize) {
+    let offset = cmp::max(mem::size_of::<ReleaseFn>(), layout.align());
+    let align = cmp::max(mem::align_of::<ReleaseFn>(), layout.align());
+    let size = offset.checked_add(layout.size()).expect("value too large");
+    (Layout::from_size_align(size, align).expect("value too large"), offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::alloc::Layout;
+    use core::ptr::NonNull;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::leak::Leaking;
+    use crate::Owned;
+
+    use super::{Allocator, Global};
+
+    struct Counting {
+        allocated: AtomicUsize,
+        deallocated: AtomicUsize,
+    }
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+            self.allocated.fetch_add(1, Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.deallocated.fetch_add(1, Ordering::Relaxed);
+            Global.deallocate(ptr, layout);
+        }
+    }
+
+    static COUNTING: Counting =
+        Counting { allocated: AtomicUsize::new(0), deallocated: AtomicUsize::new(0) };
+
+    #[test]
+    fn owned_new_in() {
+        let owned = Owned::<_, Leaking, typenum::U0>::new_in(String::from("arena"), &COUNTING);
+        assert_eq!(COUNTING.allocated.load(Ordering::Relaxed), 1);
+        assert_eq!(Owned::into_inner(owned), "arena");
+        assert_eq!(COUNTING.deallocated.load(Ordering::Relaxed), 1);
+
+        let owned = Owned::<_, Leaking, typenum::U0>::new_in(String::from("arena"), &COUNTING);
+        drop(owned);
+        assert_eq!(COUNTING.allocated.load(Ordering::Relaxed), 2);
+        assert_eq!(COUNTING.deallocated.load(Ordering::Relaxed), 2);
+    }
+}
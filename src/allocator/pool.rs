@@ -0,0 +1,156 @@
+//! Thread local and global free lists for recycling the memory of reclaimed
+//! records.
+//!
+//! Memory blocks are recycled by their layout, so they are reused by later
+//! allocations of records with the same type or at least the same size and
+//! alignment.
+
+use std::alloc::{alloc, dealloc, Layout};
+use std::cell::RefCell;
+use std::mem;
+use std::ptr::NonNull;
+use std::sync::Mutex;
+
+use crate::lazy::Lazy;
+
+/// The maximum number of free blocks of the same layout each thread keeps
+/// cached.
+const LOCAL_CAPACITY: usize = 64;
+/// The maximum number of free blocks of the same layout in the global
+/// overflow list.
+const GLOBAL_CAPACITY: usize = 1024;
+
+static GLOBAL: Lazy<Mutex<FreeLists>> = Lazy::new();
+
+thread_local!(static LOCAL: RefCell<FreeLists> = const { RefCell::new(FreeLists(Vec::new())) });
+
+/// Allocates a block of memory for the given `layout`, preferably by re-using
+/// a previously freed block of the same layout.
+#[inline]
+pub(super) fn allocate(layout: Layout) -> Option<NonNull<u8>> {
+    let recycled = LOCAL.try_with(|local| {
+        let mut local = local.borrow_mut();
+        let free = local.get_or_insert(layout);
+        if free.is_empty() {
+            // refill the local list with up to half its capacity from the global list
+            let mut global = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+            let overflow = global.get_or_insert(layout);
+            let split = overflow.len().saturating_sub(LOCAL_CAPACITY / 2);
+            free.extend(overflow.drain(split..));
+        }
+
+        free.pop()
+    });
+
+    match recycled {
+        Ok(Some(Block(ptr))) => Some(ptr),
+        _ => NonNull::new(unsafe { alloc(layout) }),
+    }
+}
+
+/// Returns the block of memory at `ptr` to the free list of the calling
+/// thread or, if it is full, to the global overflow list.
+///
+/// # Safety
+///
+/// The memory must have been allocated with the given `layout`.
+#[inline]
+pub(super) unsafe fn deallocate(ptr: NonNull<u8>, layout: Layout) {
+    let res = LOCAL.try_with(|local| {
+        let mut local = local.borrow_mut();
+        let free = local.get_or_insert(layout);
+        if free.len() == LOCAL_CAPACITY {
+            // move half the local list to the global list to avoid contention on the next free
+            let overflow = free.split_off(LOCAL_CAPACITY / 2);
+            release(layout, overflow);
+        }
+
+        free.push(Block(ptr));
+    });
+
+    if res.is_err() {
+        release(layout, Some(Block(ptr)));
+    }
+}
+
+/// Hands over the blocks of the given `layout` to the global overflow list
+/// and frees all blocks exceeding its capacity.
+#[inline]
+fn release(layout: Layout, blocks: impl IntoIterator<Item = Block>) {
+    let mut blocks = blocks.into_iter();
+    {
+        let mut global = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+        let overflow = global.get_or_insert(layout);
+        let remaining = GLOBAL_CAPACITY - overflow.len();
+        overflow.extend(blocks.by_ref().take(remaining));
+    }
+
+    for Block(ptr) in blocks {
+        unsafe { dealloc(ptr.as_ptr(), layout) };
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// FreeLists
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The lists of free blocks for each layout.
+#[derive(Debug, Default)]
+struct FreeLists(Vec<(Layout, Vec<Block>)>);
+
+/********** impl inherent *************************************************************************/
+
+impl FreeLists {
+    /// Returns the list of free blocks for the given `layout`.
+    #[inline]
+    fn get_or_insert(&mut self, layout: Layout) -> &mut Vec<Block> {
+        // there are usually only few different record layouts, so a linear search suffices
+        let idx = match self.0.iter().position(|(other, _)| *other == layout) {
+            Some(idx) => idx,
+            None => {
+                self.0.push((layout, Vec::new()));
+                self.0.len() - 1
+            }
+        };
+
+        &mut self.0[idx].1
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for FreeLists {
+    #[inline]
+    fn drop(&mut self) {
+        for (layout, free) in mem::take(&mut self.0) {
+            release(layout, free);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Block
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A free block of memory.
+#[derive(Debug)]
+struct Block(NonNull<u8>);
+
+/********** impl Send *****************************************************************************/
+
+// free blocks can be re-used by any thread
+unsafe impl Send for Block {}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::Layout;
+
+    #[test]
+    fn recycle() {
+        let layout = Layout::new::<[u64; 3]>();
+        let ptr = super::allocate(layout).unwrap();
+        unsafe { super::deallocate(ptr, layout) };
+        assert_eq!(super::allocate(layout), Some(ptr));
+        unsafe { super::deallocate(ptr, layout) };
+    }
+}
@@ -1,5 +1,6 @@
 mod compare;
 mod guard;
+mod owning;
 mod store;
 
 use core::fmt;
@@ -8,10 +9,15 @@ use core::sync::atomic::Ordering;
 
 use typenum::Unsigned;
 
+use crate::allocator::Allocator;
 use crate::internal::{Compare, GuardRef, Internal, Store};
 use crate::leak::Leaking;
 use crate::pointer::{AtomicMarkedPtr, Marked, MarkedNonNull, MarkedPointer, MarkedPtr};
-use crate::{AcquireResult, NotEqualError, Owned, Reclaim, Shared, Unlinked, Unprotected};
+use crate::{
+    AcquireResult, NotEqualError, Owned, Pointee, Protect, Reclaim, Shared, Unlinked, Unprotected,
+};
+
+pub use self::owning::OwningAtomic;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Atomic
@@ -27,20 +33,23 @@ use crate::{AcquireResult, NotEqualError, Owned, Reclaim, Shared, Unlinked, Unpr
 /// meaning it does not automatically take care of memory de-allocation when it
 /// goes out of scope.
 /// Use the [`take`][Atomic::take] method to extract an (optional) [`Owned`]
-/// value, which *does* correctly deallocate memory when it goes out of scope.
-pub struct Atomic<T, R, N> {
+/// value, which *does* correctly deallocate memory when it goes out of scope,
+/// or use an [`OwningAtomic`] for the root pointers of a data structure instead,
+/// which does so automatically.
+#[repr(transparent)]
+pub struct Atomic<T: ?Sized + Pointee, R, N> {
     inner: AtomicMarkedPtr<T, N>,
-    _marker: PhantomData<(T, R)>,
+    _marker: PhantomData<(R, T)>,
 }
 
 /********** impl Send + Sync **********************************************************************/
 
-unsafe impl<T, R: Reclaim, N: Unsigned> Send for Atomic<T, R, N> where T: Send + Sync {}
-unsafe impl<T, R: Reclaim, N: Unsigned> Sync for Atomic<T, R, N> where T: Send + Sync {}
+unsafe impl<T: ?Sized + Pointee + Send + Sync, R: Reclaim, N: Unsigned> Send for Atomic<T, R, N> {}
+unsafe impl<T: ?Sized + Pointee + Send + Sync, R: Reclaim, N: Unsigned> Sync for Atomic<T, R, N> {}
 
 /********** impl inherent *************************************************************************/
 
-impl<T, R, N> Atomic<T, R, N> {
+impl<T: ?Sized + Pointee, R, N> Atomic<T, R, N> {
     /// Creates a new `null` pointer.
     #[inline]
     pub const fn null() -> Self {
@@ -62,6 +71,15 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
         Self::from(Owned::from(val))
     }
 
+    /// Allocates a new [`Owned`] containing the given `val` with the given
+    /// `alloc` and immediately storing it an [`Atomic`].
+    #[inline]
+    pub fn new_in<A: Allocator + Send + 'static>(val: T, alloc: A) -> Self {
+        Self::from(Owned::new_in(val, alloc))
+    }
+}
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
     /// Creates a new [`Atomic`] from the given `ptr`.
     ///
     /// # Safety
@@ -188,7 +206,7 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
         order: Ordering,
         guard: impl GuardRef<'g, Reclaimer = R>,
     ) -> Option<Shared<'g, T, R, N>> {
-        guard.load_protected(self, order).value()
+        self.load_marked(order, guard).value()
     }
 
     /// Loads a value from the pointer and uses `guard` to protect it, but only
@@ -214,7 +232,7 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
         order: Ordering,
         guard: impl GuardRef<'g, Reclaimer = R>,
     ) -> Result<Option<Shared<'g, T, R, N>>, NotEqualError> {
-        guard.load_protected_if_equal(self, expected, order).map(Marked::value)
+        self.load_marked_if_equal(expected, order, guard).map(Marked::value)
     }
 
     /// Loads a value from the pointer and uses `guard` to protect it.
@@ -243,7 +261,7 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
         order: Ordering,
         guard: impl GuardRef<'g, Reclaimer = R>,
     ) -> Marked<Shared<'g, T, R, N>> {
-        guard.load_protected(self, order)
+        guard.load_protected(self.as_elem(), order).map(Shared::from_elem)
     }
 
     /// Loads a value from the pointer and uses `guard` to protect it, but only
@@ -274,7 +292,9 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
         order: Ordering,
         guard: impl GuardRef<'g, Reclaimer = R>,
     ) -> AcquireResult<'g, T, R, N> {
-        guard.load_protected_if_equal(self, expected, order)
+        guard
+            .load_protected_if_equal(self.as_elem(), expected.cast(), order)
+            .map(|marked| marked.map(Shared::from_elem))
     }
 
     /// Stores either `null` or a valid pointer to an owned heap allocated value
@@ -443,6 +463,282 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
             })
     }
 
+    /// Repeatedly loads and protects the current value and attempts to replace
+    /// it with the value determined by `func`, until either the replacement
+    /// succeeds or `func` decides to abort.
+    ///
+    /// The closure receives the currently loaded value, which is protected by
+    /// `guard`, and the value of the previous failed attempt (if any), so that
+    /// move-only types such as [`Owned`] can be re-used between retries
+    /// instead of being re-allocated.
+    /// On success, the previous and now unlinked value is returned.
+    /// If `func` aborts, the value passed to [`Decision::Abort`] is returned.
+    ///
+    /// The value is loaded with the `failure` ordering and exchanged with a
+    /// [`compare_exchange_weak`][Atomic::compare_exchange_weak] using the
+    /// `success` and `failure` orderings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering::{AcqRel, Acquire};
+    ///
+    /// use reclaim::leak::Guard;
+    /// use reclaim::prelude::*;
+    /// use reclaim::typenum::U0;
+    /// use reclaim::Decision;
+    ///
+    /// type Atomic<T> = reclaim::leak::Atomic<T, U0>;
+    /// type Owned<T> = reclaim::leak::Owned<T, U0>;
+    ///
+    /// let atomic = Atomic::new(1);
+    /// let mut guard = Guard::new();
+    ///
+    /// let res = atomic.update(&mut guard, AcqRel, Acquire, |current, prev: Option<Owned<_>>| {
+    ///     let next = match current {
+    ///         Marked::Value(shared) => *shared + 1,
+    ///         Marked::Null(_) => 0,
+    ///     };
+    ///     match prev {
+    ///         Some(mut owned) => {
+    ///             *owned = next;
+    ///             Decision::Replace(owned)
+    ///         }
+    ///         None => Decision::Replace(Owned::new(next)),
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(*res.unwrap().unwrap_value(), 1);
+    /// # let mut atomic = atomic;
+    /// # assert_eq!(*atomic.take().unwrap(), 2);
+    /// ```
+    #[inline]
+    pub fn update<G, S, F>(
+        &self,
+        guard: &mut G,
+        success: Ordering,
+        failure: Ordering,
+        mut func: F,
+    ) -> Result<Marked<Unlinked<T, R, N>>, Option<S>>
+    where
+        G: Protect<Reclaimer = R>,
+        S: Store<Item = T, MarkBits = N, Reclaimer = R>,
+        F: FnMut(Marked<Shared<T, R, N>>, Option<S>) -> Decision<S>,
+    {
+        let mut input = None;
+        loop {
+            let current = guard.protect(self.as_elem(), failure).map(Shared::from_elem);
+            let new = match func(current, input.take()) {
+                Decision::Replace(new) => new,
+                Decision::Abort(input) => return Err(input),
+            };
+
+            match self.compare_exchange_weak(current, new, success, failure) {
+                Ok(unlinked) => return Ok(unlinked),
+                Err(failure) => input = Some(failure.input),
+            }
+        }
+    }
+
+    /// Repeatedly loads and protects the current value and attempts to replace
+    /// it with the value returned by `func`, until either the replacement
+    /// succeeds or `func` returns `None`.
+    ///
+    /// Unlike [`update`][Atomic::update], the closure has to create a new
+    /// value for every attempt, which makes this method more suitable for
+    /// values that are cheap to create, such as [`Unprotected`] references.
+    /// On success, the previous and now unlinked value is returned.
+    /// If `func` returns `None`, the last loaded value is returned as an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering::{AcqRel, Acquire};
+    ///
+    /// use reclaim::leak::Guard;
+    /// use reclaim::prelude::*;
+    /// use reclaim::typenum::U1;
+    ///
+    /// type Atomic<T> = reclaim::leak::Atomic<T, U1>;
+    /// type Shared<'g, T> = reclaim::leak::Shared<'g, T, U1>;
+    /// type Unprotected<T> = reclaim::leak::Unprotected<T, U1>;
+    ///
+    /// let atomic = Atomic::new(1);
+    /// let mut guard = Guard::new();
+    ///
+    /// // set the mark bit, unless it is already set
+    /// let mark = |current: Marked<Shared<i32>>| match current {
+    ///     Marked::Value(shared) if current.decompose_tag() == 0 => {
+    ///         Some(Unprotected::marked(Shared::into_unprotected(shared), 1))
+    ///     }
+    ///     _ => None,
+    /// };
+    ///
+    /// assert!(atomic.fetch_update(&mut guard, AcqRel, Acquire, mark).is_ok());
+    /// assert!(atomic.fetch_update(&mut guard, AcqRel, Acquire, mark).is_err());
+    /// ```
+    #[inline]
+    pub fn fetch_update<G, S, F>(
+        &self,
+        guard: &mut G,
+        success: Ordering,
+        failure: Ordering,
+        mut func: F,
+    ) -> FetchUpdateResult<T, R, N>
+    where
+        G: Protect<Reclaimer = R>,
+        S: Store<Item = T, MarkBits = N, Reclaimer = R>,
+        F: FnMut(Marked<Shared<T, R, N>>) -> Option<S>,
+    {
+        loop {
+            let current = guard.protect(self.as_elem(), failure).map(Shared::from_elem);
+            let new = match func(current) {
+                Some(new) => new,
+                None => return Err(current.map(Shared::into_unprotected)),
+            };
+
+            if let Ok(unlinked) = self.compare_exchange_weak(current, new, success, failure) {
+                return Ok(unlinked);
+            }
+        }
+    }
+
+    /// Bitwise `or` with the current tag value, leaving the pointer itself
+    /// unchanged.
+    ///
+    /// Returns the previous value as an [`Unprotected`] reference with the
+    /// previous tag.
+    /// This can be used to e.g. logically delete a node in a linked list by
+    /// setting its mark bit before it is actually unlinked.
+    ///
+    /// `fetch_or_tag` takes an [`Ordering`][ordering] argument, which
+    /// describes the memory ordering of this operation.
+    /// All ordering modes are possible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` has any bits set that exceed the `N` mark bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// use reclaim::typenum::U1;
+    ///
+    /// type Atomic<T> = reclaim::leak::Atomic<T, U1>;
+    ///
+    /// let atomic = Atomic::new(1);
+    ///
+    /// let prev = atomic.fetch_or_tag(0b1, Relaxed);
+    /// assert_eq!(prev.decompose_tag(), 0);
+    /// assert_eq!(atomic.load_marked_unprotected(Relaxed).decompose_tag(), 0b1);
+    /// ```
+    ///
+    /// [ordering]: core::sync::atomic::Ordering
+    #[inline]
+    pub fn fetch_or_tag(&self, tag: usize, order: Ordering) -> Marked<Unprotected<T, R, N>> {
+        Self::assert_tag(tag);
+        unsafe { Marked::from_marked_ptr(self.inner.fetch_or(tag, order)) }
+    }
+
+    /// Bitwise `and` with the current tag value, leaving the pointer itself
+    /// unchanged.
+    ///
+    /// Returns the previous value as an [`Unprotected`] reference with the
+    /// previous tag.
+    ///
+    /// `fetch_and_tag` takes an [`Ordering`][ordering] argument, which
+    /// describes the memory ordering of this operation.
+    /// All ordering modes are possible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` has any bits set that exceed the `N` mark bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// use reclaim::typenum::U2;
+    ///
+    /// type Atomic<T> = reclaim::leak::Atomic<T, U2>;
+    ///
+    /// let atomic = Atomic::new(1);
+    /// atomic.fetch_or_tag(0b11, Relaxed);
+    ///
+    /// let prev = atomic.fetch_and_tag(0b10, Relaxed);
+    /// assert_eq!(prev.decompose_tag(), 0b11);
+    /// assert_eq!(atomic.load_marked_unprotected(Relaxed).decompose_tag(), 0b10);
+    /// assert!(atomic.load_unprotected(Relaxed).is_some());
+    /// ```
+    ///
+    /// [ordering]: core::sync::atomic::Ordering
+    #[inline]
+    pub fn fetch_and_tag(&self, tag: usize, order: Ordering) -> Marked<Unprotected<T, R, N>> {
+        Self::assert_tag(tag);
+        let mask = tag | AtomicMarkedPtr::<T, N>::POINTER_MASK;
+        unsafe { Marked::from_marked_ptr(self.inner.fetch_and(mask, order)) }
+    }
+
+    /// Stores `current` with its tag replaced by `tag` into the pointer, if the
+    /// current value is the same as `current`.
+    ///
+    /// Since the pointer itself is never changed, no value is unlinked by this
+    /// operation.
+    /// On success, the previous value is returned as an [`Unprotected`]
+    /// reference, which is guaranteed to be equal to `current`.
+    /// On failure, the actually loaded value is returned instead.
+    ///
+    /// `compare_exchange_tag` takes two [`Ordering`][ordering] arguments with
+    /// the same semantics as for [`compare_exchange`][Atomic::compare_exchange].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` has any bits set that exceed the `N` mark bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+    ///
+    /// use reclaim::typenum::U1;
+    ///
+    /// type Atomic<T> = reclaim::leak::Atomic<T, U1>;
+    ///
+    /// let atomic = Atomic::new(1);
+    /// let current = atomic.load_marked_unprotected(Relaxed);
+    ///
+    /// assert!(atomic.compare_exchange_tag(current, 0b1, Release, Relaxed).is_ok());
+    /// // the tag has changed, so the second attempt must fail
+    /// let res = atomic.compare_exchange_tag(current, 0b1, Release, Relaxed);
+    /// assert_eq!(res.unwrap_err().decompose_tag(), 0b1);
+    /// # assert_eq!(atomic.load_marked_unprotected(Acquire).decompose_tag(), 0b1);
+    /// ```
+    ///
+    /// [ordering]: core::sync::atomic::Ordering
+    #[inline]
+    pub fn compare_exchange_tag<C>(
+        &self,
+        current: C,
+        tag: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> TagExchangeResult<T, R, N>
+    where
+        C: Compare<Item = T, MarkBits = N, Reclaimer = R>,
+    {
+        Self::assert_tag(tag);
+        let current = MarkedPointer::into_marked_ptr(current);
+        let new = current.with_tag(tag);
+
+        self.inner
+            .compare_exchange(current, new, success, failure)
+            .map(|ptr| unsafe { Marked::from_marked_ptr(ptr) })
+            .map_err(|ptr| unsafe { Marked::from_marked_ptr(ptr) })
+    }
+
     /// Takes the value out of the pointer as an optional [`Owned`], leaving a
     /// `null` pointer in its place.
     ///
@@ -456,11 +752,28 @@ impl<T, R: Reclaim, N: Unsigned> Atomic<T, R, N> {
             .map(|ptr| unsafe { Owned::from_marked_non_null(ptr) })
             .value()
     }
+
+    /// Returns a reference to the `Atomic` as a pointer to the record element
+    /// of `T`, which is identical to `T` for all sized types.
+    #[inline]
+    fn as_elem(&self) -> &Atomic<T::Elem, R, N> {
+        unsafe { &*(self as *const Self as *const Atomic<T::Elem, R, N>) }
+    }
+
+    /// Asserts that `tag` fits into the mark bits of the `Atomic`.
+    #[inline]
+    fn assert_tag(tag: usize) {
+        assert_eq!(
+            tag & AtomicMarkedPtr::<T, N>::POINTER_MASK,
+            0,
+            "tag exceeds the available mark bits"
+        );
+    }
 }
 
 /********** impl inherent (Leaking) ***************************************************************/
 
-impl<T, N: Unsigned> Atomic<T, Leaking, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> Atomic<T, Leaking, N> {
     /// Loads an optional [`Shared`] reference from the `Atomic`.
     ///
     /// Since [`Leaking`] never frees memory of retired records, this is always
@@ -499,7 +812,7 @@ impl<T, N: Unsigned> Atomic<T, Leaking, N> {
 
 /********** impl Default **************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Default for Atomic<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Default for Atomic<T, R, N> {
     #[inline]
     fn default() -> Self {
         Self::null()
@@ -515,7 +828,7 @@ impl<T, R: Reclaim, N: Unsigned> From<T> for Atomic<T, R, N> {
     }
 }
 
-impl<T, R: Reclaim, N: Unsigned> From<Owned<T, R, N>> for Atomic<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> From<Owned<T, R, N>> for Atomic<T, R, N> {
     #[inline]
     fn from(owned: Owned<T, R, N>) -> Self {
         Self { inner: AtomicMarkedPtr::from(Owned::into_marked_ptr(owned)), _marker: PhantomData }
@@ -524,7 +837,7 @@ impl<T, R: Reclaim, N: Unsigned> From<Owned<T, R, N>> for Atomic<T, R, N> {
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Atomic<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Debug for Atomic<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.inner.load(Ordering::SeqCst).decompose();
@@ -534,7 +847,7 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Atomic<T, R, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Atomic<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Pointer for Atomic<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.inner.load(Ordering::SeqCst), f)
@@ -543,7 +856,38 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Atomic<T, R, N> {
 
 /********** impl Internal *************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Internal for Atomic<T, R, N> {}
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Internal for Atomic<T, R, N> {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Decision
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The decision of the closure passed to [`update`][Atomic::update] for
+/// each attempt.
+#[derive(Debug)]
+pub enum Decision<S> {
+    /// Attempt to replace the current value with the given value.
+    Replace(S),
+    /// Abort the update and return the given value to the caller.
+    Abort(Option<S>),
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// FetchUpdateResult
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Result type for [`fetch_update`][Atomic::fetch_update] operations.
+pub type FetchUpdateResult<T, R, N> =
+    Result<Marked<Unlinked<T, R, N>>, Marked<Unprotected<T, R, N>>>;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// TagExchangeResult
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Result type for [`compare_exchange_tag`][Atomic::compare_exchange_tag]
+/// operations.
+pub type TagExchangeResult<T, R, N> =
+    Result<Marked<Unprotected<T, R, N>>, Marked<Unprotected<T, R, N>>>;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // CompareExchangeFailure
@@ -554,6 +898,7 @@ impl<T, R: Reclaim, N: Unsigned> Internal for Atomic<T, R, N> {}
 #[derive(Debug)]
 pub struct CompareExchangeFailure<T, R, S, N>
 where
+    T: ?Sized + Pointee,
     R: Reclaim,
     S: Store<Item = T, MarkBits = N, Reclaimer = R>,
     N: Unsigned,
@@ -565,3 +910,88 @@ where
     // prevents construction outside of the current module
     _marker: PhantomData<R>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::leak::{Guard, Leaking};
+    use crate::{Decision, Marked, MarkedPointer, Owned};
+
+    type Atomic<T> = super::Atomic<T, Leaking, typenum::U0>;
+    type MarkedAtomic<T> = super::Atomic<T, Leaking, typenum::U1>;
+
+    #[test]
+    fn update() {
+        const THREADS: usize = 4;
+        const INCREMENTS: usize = 100;
+
+        let atomic = Arc::new(Atomic::new(0));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let atomic = Arc::clone(&atomic);
+                thread::spawn(move || {
+                    let mut guard = Guard::new();
+                    for _ in 0..INCREMENTS {
+                        let res = atomic.update(&mut guard, AcqRel, Acquire, |current, prev| {
+                            let next = *current.unwrap_value() + 1;
+                            let mut owned = prev.unwrap_or_else(|| Owned::new(0));
+                            *owned = next;
+                            Decision::Replace(owned)
+                        });
+
+                        assert!(res.is_ok());
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut guard = Guard::new();
+        let current = atomic.load(Acquire, &mut guard).unwrap();
+        assert_eq!(*current, THREADS * INCREMENTS);
+    }
+
+    #[test]
+    fn update_abort() {
+        let atomic = Atomic::new(1);
+        let mut guard = Guard::new();
+
+        let res =
+            atomic.update(&mut guard, AcqRel, Acquire, |_, _| Decision::Abort(Some(Owned::new(2))));
+        assert_eq!(res.map(|_| ()).unwrap_err().map(Owned::into_inner), Some(2));
+
+        let res =
+            atomic.fetch_update(&mut guard, AcqRel, Acquire, |_| None::<Marked<Owned<_, _, _>>>);
+        assert_eq!(unsafe { *res.map(|_| ()).unwrap_err().unwrap_value().deref_unprotected() }, 1);
+    }
+
+    #[test]
+    fn tag_ops() {
+        let atomic = MarkedAtomic::new(1);
+        let current = atomic.load_marked_unprotected(Relaxed);
+        let raw = current.into_marked_ptr();
+
+        // the pointer must be preserved by all tag operations
+        assert_eq!(atomic.fetch_or_tag(0b1, Relaxed).into_marked_ptr(), raw);
+        assert_eq!(atomic.load_raw(Relaxed), raw.with_tag(0b1));
+        assert_eq!(atomic.fetch_and_tag(0b0, Relaxed).decompose_tag(), 0b1);
+        assert_eq!(atomic.load_raw(Relaxed), raw);
+
+        assert!(atomic.compare_exchange_tag(current, 0b1, Relaxed, Relaxed).is_ok());
+        let res = atomic.compare_exchange_tag(current, 0b0, Relaxed, Relaxed);
+        assert_eq!(res.unwrap_err().into_marked_ptr(), raw.with_tag(0b1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn tag_exceeds_mark_bits() {
+        let atomic = MarkedAtomic::new(1);
+        atomic.fetch_or_tag(0b10, Relaxed);
+    }
+}
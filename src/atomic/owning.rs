@@ -0,0 +1,216 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ptr;
+
+use typenum::Unsigned;
+
+use crate::allocator::Allocator;
+use crate::atomic::Atomic;
+use crate::{Owned, Pointee, Reclaim};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// OwningAtomic
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An [`Atomic`] that owns its current value and drops it when it goes out of
+/// scope.
+///
+/// This type de-references to [`Atomic`], so it has the same API and can be
+/// used anywhere an `&Atomic` is expected.
+/// Unlike an `Atomic`, data structures using this type do not require a
+/// hand-written [`Drop`] implementation that [`take`][OwningAtomic::take]s
+/// the value out of the pointer.
+///
+/// Note, that only the value that is *currently* stored in the pointer is
+/// dropped, all values that were previously swapped out or unlinked still
+/// have to be retired or dropped manually.
+///
+/// An `OwningAtomic` is intended only for *root* pointers of a data structure
+/// (e.g. the `head` of a stack), not for the links *inside* records that get
+/// retired.
+/// When a record is unlinked, its successor usually remains reachable from
+/// the data structure, so reclaiming the retired record would also drop the
+/// still linked successor.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::Ordering::Relaxed;
+///
+/// use reclaim::typenum::U0;
+///
+/// type Atomic<T> = reclaim::leak::Atomic<T, U0>;
+/// type OwningAtomic<T> = reclaim::leak::OwningAtomic<T, U0>;
+///
+/// struct Stack {
+///     head: OwningAtomic<Node>,
+/// }
+///
+/// struct Node {
+///     elem: String,
+///     // links inside of records must not own their successors
+///     next: Atomic<Node>,
+/// }
+///
+/// let stack = Stack {
+///     head: OwningAtomic::new(Node { elem: String::from("first"), next: Atomic::null() }),
+/// };
+///
+/// assert!(stack.head.load_unprotected(Relaxed).is_some());
+/// // dropping the stack drops the current head node
+/// drop(stack);
+/// ```
+#[repr(transparent)]
+pub struct OwningAtomic<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> {
+    inner: Atomic<T, R, N>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> OwningAtomic<T, R, N> {
+    /// Creates a new `null` pointer.
+    #[inline]
+    pub const fn null() -> Self {
+        Self { inner: Atomic::null() }
+    }
+
+    /// Takes the value out of the pointer as an optional [`Owned`], leaving a
+    /// `null` pointer in its place.
+    #[inline]
+    pub fn take(&mut self) -> Option<Owned<T, R, N>> {
+        self.inner.take()
+    }
+
+    /// Consumes `self` and returns the inner [`Atomic`] without dropping its
+    /// current value.
+    #[inline]
+    pub fn into_atomic(self) -> Atomic<T, R, N> {
+        let owning = ManuallyDrop::new(self);
+        unsafe { ptr::read(&owning.inner) }
+    }
+}
+
+impl<T, R: Reclaim, N: Unsigned> OwningAtomic<T, R, N> {
+    /// Allocates a new [`Owned`] containing the given `val` and immediately
+    /// storing it in the pointer.
+    #[inline]
+    pub fn new(val: T) -> Self {
+        Self { inner: Atomic::new(val) }
+    }
+
+    /// Allocates a new [`Owned`] containing the given `val` with the given
+    /// `alloc` and immediately storing it in the pointer.
+    #[inline]
+    pub fn new_in<A: Allocator + Send + 'static>(val: T, alloc: A) -> Self {
+        Self { inner: Atomic::new_in(val, alloc) }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Default for OwningAtomic<T, R, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Deref for OwningAtomic<T, R, N> {
+    type Target = Atomic<T, R, N>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Drop for OwningAtomic<T, R, N> {
+    #[inline]
+    fn drop(&mut self) {
+        // the mutable reference ensures no other thread can still access the current value
+        self.inner.take();
+    }
+}
+
+/********** impl From *****************************************************************************/
+
+impl<T, R: Reclaim, N: Unsigned> From<T> for OwningAtomic<T, R, N> {
+    #[inline]
+    fn from(val: T) -> Self {
+        Self::new(val)
+    }
+}
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> From<Owned<T, R, N>> for OwningAtomic<T, R, N> {
+    #[inline]
+    fn from(owned: Owned<T, R, N>) -> Self {
+        Self { inner: Atomic::from(owned) }
+    }
+}
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> From<Atomic<T, R, N>> for OwningAtomic<T, R, N> {
+    #[inline]
+    fn from(atomic: Atomic<T, R, N>) -> Self {
+        Self { inner: atomic }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Debug for OwningAtomic<T, R, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("OwningAtomic").field(&self.inner).finish()
+    }
+}
+
+/********** impl Pointer **************************************************************************/
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Pointer for OwningAtomic<T, R, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::leak::Leaking;
+
+    type Atomic<T> = crate::Atomic<T, Leaking, U0>;
+    type OwningAtomic<T> = super::OwningAtomic<T, Leaking, U0>;
+
+    fn count(atomic: &Atomic<Arc<()>>) -> usize {
+        atomic
+            .load_unprotected(Relaxed)
+            .map_or(0, |ptr| Arc::strong_count(unsafe { ptr.deref_unprotected() }))
+    }
+
+    #[test]
+    fn drop() {
+        let arc = Arc::new(());
+        let owning = OwningAtomic::new(Arc::clone(&arc));
+        assert_eq!(count(&owning), 2);
+        std::mem::drop(owning);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn into_atomic() {
+        let arc = Arc::new(());
+        let mut atomic = OwningAtomic::new(Arc::clone(&arc)).into_atomic();
+        assert_eq!(Arc::strong_count(&arc), 2);
+        std::mem::drop(atomic.take());
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+}
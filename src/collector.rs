@@ -0,0 +1,334 @@
+//! A background thread for periodically reclaiming retired records.
+//!
+//! A [`Collector`] owns a thread, which calls
+//! [`try_reclaim_quiescent`][GlobalReclaim::try_reclaim_quiescent] of a
+//! reclamation scheme whenever its interval elapses or when it is explicitly
+//! [`signal`][Collector::signal]ed.
+//! Since the collector thread never holds any references to shared values, it
+//! does not hold up the reclamation of records retired by other threads, even
+//! for schemes which wait for the progress of all participating threads.
+//!
+//! Note, that many reclamation schemes store retired records in thread local
+//! storage, so that the collector thread can only reclaim records that have
+//! been abandoned by exited threads or that are stored globally.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use reclaim::collector::Collector;
+//! use reclaim::leak::Leaking;
+//!
+//! let mut collector = Collector::<Leaking>::new(Duration::from_millis(10));
+//! collector.start().unwrap();
+//! assert!(collector.is_running());
+//!
+//! collector.signal();
+//! collector.stop();
+//! collector.join().unwrap();
+//! assert!(!collector.is_running());
+//! ```
+
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::GlobalReclaim;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Collector
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A handle to a background thread, which periodically reclaims retired
+/// records of the reclamation scheme `R`.
+///
+/// The thread is stopped and joined when the collector is dropped.
+pub struct Collector<R> {
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<()>>,
+    _marker: PhantomData<fn() -> R>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: GlobalReclaim> Collector<R> {
+    /// Creates a new collector, which reclaims records every `interval`
+    /// once it has been started.
+    #[inline]
+    pub fn new(interval: Duration) -> Self {
+        let state = State { interval, stopped: false, signalled: false };
+        Self {
+            shared: Arc::new(Shared { state: Mutex::new(state), condvar: Condvar::new() }),
+            handle: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the current reclamation interval.
+    #[inline]
+    pub fn interval(&self) -> Duration {
+        self.shared.lock().interval
+    }
+
+    /// Sets the reclamation interval, which takes effect after the current
+    /// interval has elapsed.
+    #[inline]
+    pub fn set_interval(&self, interval: Duration) {
+        self.shared.lock().interval = interval;
+    }
+
+    /// Returns `true` if the collector thread has been started and not yet
+    /// joined.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Starts the collector thread, if it is not already running.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the thread can not be spawned.
+    #[inline]
+    pub fn start(&mut self) -> io::Result<()> {
+        if self.handle.is_some() {
+            return Ok(());
+        }
+
+        self.shared.lock().stopped = false;
+        let shared = Arc::clone(&self.shared);
+        let handle = thread::Builder::new()
+            .name(String::from("reclaim-collector"))
+            .spawn(move || shared.run::<R>())?;
+        self.handle = Some(handle);
+
+        Ok(())
+    }
+
+    /// Wakes up the collector thread, so it reclaims records immediately.
+    #[inline]
+    pub fn signal(&self) {
+        self.shared.lock().signalled = true;
+        self.shared.condvar.notify_one();
+    }
+
+    /// Requests the collector thread to stop after finishing its current
+    /// reclamation attempt.
+    ///
+    /// The thread has to be [`join`][Collector::join]ed before it can be
+    /// started again.
+    #[inline]
+    pub fn stop(&self) {
+        self.shared.lock().stopped = true;
+        self.shared.condvar.notify_one();
+    }
+
+    /// Waits for the collector thread to finish after it has been
+    /// [`stop`][Collector::stop]ped.
+    ///
+    /// This is a no-op, if the thread is not running.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the collector thread has panicked.
+    #[inline]
+    pub fn join(&mut self) -> thread::Result<()> {
+        match self.handle.take() {
+            Some(handle) => handle.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R> fmt::Debug for Collector<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Collector").field("handle", &self.handle).finish()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<R> Drop for Collector<R> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.shared.lock().stopped = true;
+            self.shared.condvar.notify_one();
+            let _ = handle.join();
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Shared
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The state shared between a collector and its thread.
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Shared {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Runs the collector loop until the collector is stopped.
+    #[inline]
+    fn run<R: GlobalReclaim>(&self) {
+        let mut state = self.lock();
+        loop {
+            if !state.stopped && !state.signalled {
+                let interval = state.interval;
+                state = match self.condvar.wait_timeout(state, interval) {
+                    Ok((state, _)) => state,
+                    Err(err) => err.into_inner().0,
+                };
+            }
+
+            if state.stopped {
+                return;
+            }
+
+            state.signalled = false;
+            drop(state);
+
+            R::try_reclaim_quiescent();
+            state = self.lock();
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// State
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+struct State {
+    interval: Duration,
+    stopped: bool,
+    signalled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::leak::Leaking;
+
+    use super::Collector;
+
+    #[test]
+    fn start_stop_restart() {
+        let mut collector = Collector::<Leaking>::new(Duration::from_millis(1));
+        assert!(!collector.is_running());
+        collector.start().unwrap();
+        collector.start().unwrap();
+        assert!(collector.is_running());
+
+        collector.set_interval(Duration::from_secs(60));
+        assert_eq!(collector.interval(), Duration::from_secs(60));
+        collector.signal();
+        collector.stop();
+        collector.join().unwrap();
+        assert!(!collector.is_running());
+
+        collector.start().unwrap();
+        assert!(collector.is_running());
+    }
+
+    #[cfg(feature = "hazard")]
+    #[test]
+    fn reclaim_abandoned() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::thread;
+
+        use typenum::U0;
+
+        use crate::hazard::{Guard, Hazard};
+        use crate::prelude::*;
+        use crate::test_util::{self, DropCount};
+
+        type Atomic<T> = crate::hazard::Atomic<T, U0>;
+        type Owned<T> = crate::hazard::Owned<T, U0>;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Arc::new(Atomic::new(DropCount(Arc::clone(&count))));
+        let mut guard = Guard::new();
+        let _ = atomic.load(Ordering::Acquire, &mut guard);
+
+        // the record is still protected when it is abandoned by the exiting thread
+        let handle = {
+            let atomic = Arc::clone(&atomic);
+            thread::spawn(move || {
+                let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+                unsafe { unlinked.retire() };
+            })
+        };
+
+        handle.join().unwrap();
+        guard.release();
+
+        let mut collector = Collector::<Hazard>::new(Duration::from_secs(60));
+        collector.start().unwrap();
+        test_util::reclaim_until(&count, 1, || {
+            collector.signal();
+            thread::sleep(Duration::from_millis(1));
+        });
+    }
+
+    #[cfg(feature = "qsbr")]
+    #[test]
+    fn reclaim_qsbr() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::thread;
+
+        use typenum::U0;
+
+        use crate::qsbr::{Guard, Qsbr};
+        use crate::test_util::{self, DropCount};
+
+        type Atomic<T> = crate::qsbr::Atomic<T, U0>;
+        type Owned<T> = crate::qsbr::Owned<T, U0>;
+
+        let mut collector = Collector::<Qsbr>::new(Duration::from_millis(1));
+        collector.start().unwrap();
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Arc::new(Atomic::new(DropCount(Arc::clone(&count))));
+        let guard = Guard::new();
+
+        // the record can not be reclaimed by the exiting thread, since this thread is online
+        let handle = {
+            let atomic = Arc::clone(&atomic);
+            thread::spawn(move || {
+                let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+                unsafe { unlinked.retire() };
+            })
+        };
+
+        handle.join().unwrap();
+        drop(guard);
+        Qsbr::quiescent_state();
+
+        // the collector thread must not hold up the grace period itself
+        test_util::reclaim_until(&count, 1, || {
+            collector.signal();
+            thread::sleep(Duration::from_millis(1));
+        });
+    }
+}
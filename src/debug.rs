@@ -0,0 +1,630 @@
+//! A memory reclamation scheme for debugging, which detects common misuses
+//! of the reclamation interface.
+//!
+//! The [`Checked`] scheme never de-allocates the memory of retired records.
+//! Instead, records are *quarantined*: once it is safe to do so, their
+//! contents are dropped in place and their [`Header`]s are marked as
+//! reclaimed, but remain readable.
+//! This allows the scheme to panic with a diagnostic message instead of
+//! silently corrupting the heap whenever
+//!
+//! - a [`Shared`][crate::Shared] reference is dereferenced after its record
+//!   has been reclaimed,
+//! - a record is retired more than once or
+//! - a retired record is loaded through a [`Guard`] while it is still
+//!   reachable from an [`Atomic`][crate::Atomic].
+//!
+//! Every header additionally contains a canary value, which is checked on
+//! every access in order to detect pointers to memory not allocated as part
+//! of a record or records that have been overwritten.
+//!
+//! Retired records are reclaimed once all guards that existed at the time of
+//! their retirement have been dropped.
+//! Since its guards protect all loaded values, the scheme can replace any
+//! other scheme in tests.
+//! Since the memory of retired records is never freed, this scheme is **not**
+//! intended for use in production.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::debug::{Checked, Guard, Owned, State};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::debug::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let mut guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+//! assert_eq!(Checked::state(&shared), State::Live);
+//!
+//! let unlinked = atomic.swap(Owned::new(2), Ordering::AcqRel).unwrap();
+//! unsafe { unlinked.retire() };
+//! assert_eq!(Checked::state(&shared), State::Retired);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+use core::mem;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, ProtectRegion, Reclaim, Record};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Checked`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Checked, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Checked`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Checked, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Checked`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Checked, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Checked`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, Checked, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Checked`]
+/// reclamation scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Checked, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Checked`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Checked, N>;
+
+/// The function for dropping the contents of a type-erased record in place.
+type DropInPlace = unsafe fn(NonNull<Header>);
+
+/// The value every valid header contains.
+const CANARY: usize = 0x5AFE_C0DE;
+
+static GLOBAL: Lazy<Mutex<Global>> = Lazy::new();
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Checked
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A memory reclamation scheme for debugging, which panics on detecting any
+/// use-after-reclaim, double retire or premature retire.
+#[derive(Debug, Default)]
+pub struct Checked;
+
+/********** impl inherent *************************************************************************/
+
+impl Checked {
+    /// Returns the current [`State`] of the record referenced by `shared`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shared` does not reference a valid record.
+    #[inline]
+    pub fn state<T, N: Unsigned>(shared: &Shared<'_, T, N>) -> State {
+        let elem = shared.as_marked_ptr().decompose_ptr();
+        unsafe { Record::<T, Self>::header_from_raw(elem) }.checked_state(elem as *const ())
+    }
+
+    /// Marks the `unlinked` record as retired and quarantines it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the record has already been retired before.
+    #[inline]
+    unsafe fn retire_record<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        let elem = MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null();
+        let header = Record::<T, Self>::header_from_raw_non_null(elem);
+        header.check_canary(elem.as_ptr() as *const ());
+
+        if let Err(state) = header.transition(State::Live, State::Retired) {
+            panic!(
+                "record at {:p} retired while already in state {:?} (double retire)",
+                elem.as_ptr(),
+                state
+            );
+        }
+
+        lock_global().quarantine(NonNull::from(header), drop_in_place::<T>);
+        Self::try_reclaim();
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Checked {
+    type Guard = Guard;
+
+    /// Reclaims all quarantined records that were retired before the oldest
+    /// currently existing guard was created.
+    #[inline]
+    fn try_reclaim() {
+        // the records are taken out, because dropping them may retire further records
+        let reclaimable = lock_global().take_reclaimable();
+        for quarantined in reclaimable {
+            unsafe { quarantined.reclaim() };
+        }
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Checked {
+    type Local = ();
+    type RecordHeader = Header;
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(_: &(), unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(_: &(), unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+
+    /// Checks that the record containing `elem` has not yet been reclaimed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the record has been reclaimed or if `elem` does not point to
+    /// a valid record.
+    #[inline]
+    fn on_access<T>(elem: NonNull<T>) {
+        let header = unsafe { Record::<T, Self>::header_from_raw_non_null(elem) };
+        if header.checked_state(elem.as_ptr() as *const ()) == State::Reclaimed {
+            panic!("shared reference to record at {:p} used after reclamation", elem.as_ptr());
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// State
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The state of a record in the [`Checked`] reclamation scheme.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum State {
+    /// The record has not been retired.
+    Live,
+    /// The record has been retired, but not yet reclaimed.
+    Retired,
+    /// The record's contents have been dropped.
+    Reclaimed,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl State {
+    #[inline]
+    fn from_usize(state: usize) -> Option<Self> {
+        match state {
+            0 => Some(State::Live),
+            1 => Some(State::Retired),
+            2 => Some(State::Reclaimed),
+            _ => None,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Header
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`RecordHeader`][Reclaim::RecordHeader] type for the [`Checked`]
+/// reclamation scheme, which contains the record's [`State`] and a canary
+/// value.
+#[derive(Debug)]
+pub struct Header {
+    canary: usize,
+    state: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Header {
+    /// Returns the current state of the record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is corrupted.
+    #[inline]
+    pub fn state(&self) -> State {
+        self.checked_state(ptr::null())
+    }
+
+    /// Checks the canary and returns the current state of the record
+    /// containing `elem`.
+    #[inline]
+    fn checked_state(&self, elem: *const ()) -> State {
+        self.check_canary(elem);
+        let state = self.state.load(Ordering::Acquire);
+        State::from_usize(state).unwrap_or_else(|| {
+            panic!("record at {:p} has corrupted state {:#x}", elem, state);
+        })
+    }
+
+    /// Checks that the header contains the expected canary value.
+    #[inline]
+    fn check_canary(&self, elem: *const ()) {
+        let canary = self.canary;
+        if canary != CANARY {
+            panic!(
+                "record at {:p} has corrupted canary {:#x} (expected {:#x})",
+                elem, canary, CANARY
+            );
+        }
+    }
+
+    /// Transitions the state from `current` to `new` and returns the actual
+    /// state on failure.
+    #[inline]
+    fn transition(&self, current: State, new: State) -> Result<(), State> {
+        self.state
+            .compare_exchange(current as usize, new as usize, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| ())
+            .map_err(|state| State::from_usize(state).expect("corrupted record state"))
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Header {
+    #[inline]
+    fn default() -> Self {
+        Self { canary: CANARY, state: AtomicUsize::new(State::Live as usize) }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Checked`] reclamation
+/// scheme.
+///
+/// A guard protects all values loaded during its lifetime and keeps track of
+/// all records it has explicitly protected.
+#[derive(Debug)]
+pub struct Guard {
+    id: usize,
+    protected: Vec<NonNull<Header>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard.
+    #[inline]
+    pub fn new() -> Self {
+        Self { id: lock_global().register(None), protected: Vec::new() }
+    }
+
+    /// Returns the number of records the guard has explicitly protected since
+    /// its creation or its last release.
+    #[inline]
+    pub fn protected_count(&self) -> usize {
+        self.protected.len()
+    }
+
+    /// Loads a value from `atomic` and checks the state of its record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the loaded record has already been retired while still
+    /// being reachable through `atomic`.
+    #[inline]
+    fn load_checked<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> MarkedPtr<T, N> {
+        loop {
+            let marked = atomic.load_raw(order);
+            let elem = marked.decompose_ptr();
+            if elem.is_null() {
+                return marked;
+            }
+
+            let header = unsafe { Record::<T, Checked>::header_from_raw(elem) };
+            let state = header.checked_state(elem as *const ());
+            if state == State::Live {
+                self.protected.push(NonNull::from(header));
+                return marked;
+            }
+
+            // the record may have been unlinked and retired after it was loaded
+            if atomic.load_raw(Ordering::SeqCst).decompose_ptr() == elem {
+                panic!(
+                    "record at {:p} is still reachable although it has been retired (state: {:?})",
+                    elem, state
+                );
+            }
+        }
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    /// Creates a new guard, which protects the same records as `self`.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { id: lock_global().register(Some(self.id)), protected: self.protected.clone() }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    /// Checks that none of the protected records has been reclaimed while
+    /// the guard existed and possibly reclaims quarantined records.
+    #[inline]
+    fn drop(&mut self) {
+        for header in self.protected.drain(..) {
+            let state = unsafe { header.as_ref() }.checked_state(ptr::null());
+            assert_ne!(state, State::Reclaimed, "protected record reclaimed while guarded");
+        }
+
+        lock_global().unregister(self.id);
+        Checked::try_reclaim();
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Checked;
+
+    /// Clears the list of tracked records.
+    ///
+    /// Since the guard protects all values as long as it exists, this does
+    /// not make any records reclaimable.
+    #[inline]
+    fn release(&mut self) {
+        self.protected.clear();
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(self.load_checked(atomic, order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        match self.load_checked(atomic, order) {
+            raw if raw == expected => Ok(unsafe { Marked::from_marked_ptr(raw) }),
+            _ => Err(NotEqualError),
+        }
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl ProtectRegion for Guard {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The ids of all existing guards and all quarantined records.
+#[derive(Debug, Default)]
+struct Global {
+    next_id: usize,
+    guards: BTreeMap<usize, usize>,
+    quarantine: Vec<Quarantined>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Registers a new guard and returns its id, which is either a new one or
+    /// the `shared` id of an existing guard.
+    #[inline]
+    fn register(&mut self, shared: Option<usize>) -> usize {
+        let id = shared.unwrap_or_else(|| {
+            let id = self.next_id;
+            self.next_id += 1;
+            id
+        });
+
+        *self.guards.entry(id).or_insert(0) += 1;
+        id
+    }
+
+    /// Unregisters a guard with the given `id`.
+    #[inline]
+    fn unregister(&mut self, id: usize) {
+        let count = self.guards.get_mut(&id).expect("unregistered guard");
+        *count -= 1;
+        if *count == 0 {
+            self.guards.remove(&id);
+        }
+    }
+
+    /// Quarantines the retired record with the given `header` until all
+    /// currently existing guards have been dropped.
+    #[inline]
+    fn quarantine(&mut self, header: NonNull<Header>, drop: DropInPlace) {
+        self.quarantine.push(Quarantined { seal: self.next_id, header, drop });
+    }
+
+    /// Removes and returns all quarantined records that were retired before
+    /// the oldest currently existing guard was created.
+    #[inline]
+    fn take_reclaimable(&mut self) -> Vec<Quarantined> {
+        let oldest = self.guards.keys().next().copied().unwrap_or(usize::MAX);
+        let (reclaimable, remaining) =
+            mem::take(&mut self.quarantine).into_iter().partition(|q| q.seal <= oldest);
+        self.quarantine = remaining;
+        reclaimable
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Quarantined
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record, which has not yet been reclaimed.
+#[derive(Debug)]
+struct Quarantined {
+    seal: usize,
+    header: NonNull<Header>,
+    drop: DropInPlace,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Quarantined {
+    /// Drops the record's contents and marks it as reclaimed.
+    ///
+    /// The record's memory is deliberately leaked.
+    #[inline]
+    unsafe fn reclaim(self) {
+        (self.drop)(self.header);
+        let _ = self.header.as_ref().transition(State::Retired, State::Reclaimed);
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Quarantined {}
+
+/********** helper functions **********************************************************************/
+
+/// Locks the global state, ignoring any poisoning caused by detected errors.
+#[inline]
+fn lock_global() -> MutexGuard<'static, Global> {
+    GLOBAL.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Drops the element of type `T` of the record containing the given
+/// `header` in place.
+#[inline]
+unsafe fn drop_in_place<T>(header: NonNull<Header>) {
+    let record = (header.as_ptr() as usize) - Record::<T, Checked>::offset_header();
+    let elem = record + Record::<T, Checked>::offset_elem();
+    ptr::drop_in_place(elem as *mut T);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Checked, Guard, State};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+    type Shared<'g, T> = super::Shared<'g, T, U0>;
+
+    fn reclaim_until(shared: &Shared<'_, i32>, state: State) {
+        for _ in 0..1_000 {
+            Checked::try_reclaim();
+            if Checked::state(shared) == state {
+                return;
+            }
+
+            thread::yield_now();
+        }
+
+        panic!("records were not reclaimed");
+    }
+
+    #[test]
+    fn reclaim_after_guard() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        Checked::try_reclaim();
+        assert_eq!(Checked::state(&shared), State::Retired);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        drop(guard);
+        test_util::reclaim_until(&count, 1, Checked::try_reclaim);
+    }
+
+    #[test]
+    #[should_panic(expected = "used after reclamation")]
+    fn use_after_reclaim() {
+        let atomic = Atomic::new(1);
+
+        let mut guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        // simulates a bug by extending the reference's lifetime beyond the guard
+        let shared: Shared<'static, i32> = unsafe { Shared::cast(shared) };
+        unsafe { atomic.swap(Owned::none(), Ordering::AcqRel).unwrap().retire() };
+        drop(guard);
+
+        reclaim_until(&shared, State::Reclaimed);
+        let _ = *shared;
+    }
+
+    #[test]
+    #[should_panic(expected = "double retire")]
+    fn double_retire() {
+        let atomic = Atomic::new(1);
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        let raw = unlinked.as_marked_ptr();
+
+        unsafe {
+            unlinked.retire();
+            Checked::retire_raw(raw);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "still reachable")]
+    fn retire_reachable() {
+        let atomic = Atomic::new(1);
+        unsafe { Checked::retire_raw(atomic.load_raw(Ordering::Relaxed)) };
+
+        let mut guard = Guard::new();
+        let _ = atomic.load(Ordering::Acquire, &mut guard);
+    }
+}
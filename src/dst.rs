@@ -0,0 +1,539 @@
+//! Records for dynamically sized types such as slices, string slices and
+//! trait objects, which are stored behind thin pointers.
+//!
+//! Since marked pointers must be thin, a record can not contain a value of a
+//! dynamically sized type `T` directly.
+//! Instead, the element of such a record is an [`Unsized<T>`], which stores
+//! the value's wide pointer (i.e. its address together with its length or
+//! vtable) next to the record's header, while the value itself is stored
+//! inline behind the record in the same allocation.
+//! This way, an [`Atomic<T>`][crate::Atomic] still only requires a single
+//! word and retired records are dropped and de-allocated like any other
+//! record.
+//!
+//! The [`Pointee`] trait determines the element type of the records for any
+//! type `T`.
+//! It is implemented for all sized types, slices, string slices and the
+//! `dyn Any` trait objects.
+//! Other trait objects can be supported with the [`impl_pointee`][crate::impl_pointee]
+//! macro.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::typenum::U0;
+//!
+//! type Atomic<T> = reclaim::leak::Atomic<T, U0>;
+//! type Owned<T> = reclaim::leak::Owned<T, U0>;
+//!
+//! trait Greet {
+//!     fn greet(&self) -> String;
+//! }
+//!
+//! struct English;
+//!
+//! impl Greet for English {
+//!     fn greet(&self) -> String {
+//!         String::from("hello")
+//!     }
+//! }
+//!
+//! reclaim::impl_pointee!(dyn Greet);
+//!
+//! let key: Atomic<[u8]> = Atomic::from(Owned::from_slice(b"key"));
+//! let shared = key.load_shared(Ordering::Relaxed).unwrap();
+//! assert_eq!(&*shared, b"key");
+//!
+//! let greeter: Atomic<dyn Greet> =
+//!     Atomic::from(Owned::<dyn Greet>::new_unsize(English, |english| english));
+//! let shared = greeter.load_shared(Ordering::Relaxed).unwrap();
+//! assert_eq!(shared.greet(), "hello");
+//! # let (mut key, mut greeter) = (key, greeter);
+//! # drop(key.take());
+//! # drop(greeter.take());
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{alloc::handle_alloc_error, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::alloc::handle_alloc_error;
+
+use core::alloc::Layout;
+use core::any::Any;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+use typenum::Unsigned;
+
+use crate::allocator::{self, Allocator, Global, Release};
+use crate::pointer::MarkedNonNull;
+use crate::{Owned, Reclaim, Record};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Pointee (trait)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A trait for all types that records can be allocated for and that can hence
+/// be pointed to by (thin) marked pointers.
+///
+/// For sized types, the record element is the value itself.
+/// For slices, string slices and trait objects, the record element is an
+/// [`Unsized`], which stores the wide pointer to the value.
+///
+/// # Safety
+///
+/// The trait is implemented for all sized types, slices and string slices.
+/// Trait objects must only implement it through the
+/// [`impl_pointee`][crate::impl_pointee] macro.
+pub unsafe trait Pointee {
+    /// The (sized) element type of the records containing a `Self`.
+    type Elem: Sized;
+
+    /// Returns the (possibly wide) pointer to the value contained in the
+    /// record element at `elem`.
+    ///
+    /// # Safety
+    ///
+    /// The `elem` pointer must point at a valid record element.
+    unsafe fn from_elem(elem: NonNull<Self::Elem>) -> NonNull<Self>;
+}
+
+/********** blanket impl **************************************************************************/
+
+unsafe impl<T> Pointee for T {
+    type Elem = T;
+
+    #[inline]
+    unsafe fn from_elem(elem: NonNull<Self::Elem>) -> NonNull<Self> {
+        elem
+    }
+}
+
+/********** impl slices ***************************************************************************/
+
+unsafe impl<T> Pointee for [T] {
+    type Elem = Unsized<[T]>;
+
+    #[inline]
+    unsafe fn from_elem(elem: NonNull<Self::Elem>) -> NonNull<Self> {
+        elem.as_ref().as_non_null()
+    }
+}
+
+unsafe impl Pointee for str {
+    type Elem = Unsized<str>;
+
+    #[inline]
+    unsafe fn from_elem(elem: NonNull<Self::Elem>) -> NonNull<Self> {
+        elem.as_ref().as_non_null()
+    }
+}
+
+/********** impl trait objects ********************************************************************/
+
+/// Implements the [`Pointee`][crate::Pointee] trait for the given trait object
+/// types, so records can be allocated for them with
+/// [`Owned::new_unsize`][crate::Owned::new_unsize].
+///
+/// # Examples
+///
+/// ```
+/// trait Shape {
+///     fn area(&self) -> f64;
+/// }
+///
+/// reclaim::impl_pointee!(dyn Shape, dyn Shape + Send + Sync);
+/// ```
+#[macro_export]
+macro_rules! impl_pointee {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            unsafe impl $crate::Pointee for $ty {
+                type Elem = $crate::dst::Unsized<$ty>;
+
+                #[inline]
+                unsafe fn from_elem(
+                    elem: ::core::ptr::NonNull<Self::Elem>,
+                ) -> ::core::ptr::NonNull<Self> {
+                    elem.as_ref().as_non_null()
+                }
+            }
+        )+
+    };
+}
+
+impl_pointee!(dyn Any, dyn Any + Send, dyn Any + Send + Sync);
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Unsized
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The record element for values of a dynamically sized type `T`, which owns
+/// the value stored behind the record and its wide pointer.
+pub struct Unsized<T: ?Sized> {
+    ptr: NonNull<T>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T: ?Sized> Unsized<T> {
+    /// Returns the wide pointer to the owned value.
+    #[inline]
+    pub fn as_non_null(&self) -> NonNull<T> {
+        self.ptr
+    }
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<T: ?Sized + Send> Send for Unsized<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for Unsized<T> {}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T: ?Sized> fmt::Debug for Unsized<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Unsized").field("ptr", &self.ptr).finish()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<T: ?Sized> Drop for Unsized<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.ptr.as_ptr()) };
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Owned
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T, R, N> Owned<T, R, N>
+where
+    T: ?Sized + Pointee<Elem = Unsized<T>>,
+    R: Reclaim,
+    N: Unsigned,
+{
+    /// Allocates a new record containing `value`, which is unsized to a `T`
+    /// by the given `coerce` closure.
+    ///
+    /// The closure is usually the identity function (`|value| value`) and
+    /// relies on an unsizing coercion, e.g. from an array to a slice or from a
+    /// concrete type to a trait object.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `coerce` returns a reference to anything else than its
+    /// entire argument.
+    #[inline]
+    pub fn new_unsize<U>(value: U, coerce: impl FnOnce(&mut U) -> &mut T) -> Self {
+        Self::new_unsize_in(value, coerce, Global)
+    }
+
+    /// Allocates a new record containing `value` with the given `alloc`,
+    /// which is unsized to a `T` by the given `coerce` closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `coerce` returns a reference to anything else than its
+    /// entire argument.
+    #[inline]
+    pub fn new_unsize_in<U>(
+        value: U,
+        coerce: impl FnOnce(&mut U) -> &mut T,
+        alloc: impl Allocator + Send + 'static,
+    ) -> Self {
+        let elem = alloc_unsized::<T, R, _>(Layout::new::<U>(), alloc, |ptr| unsafe {
+            let ptr = ptr.cast::<U>();
+            ptr.as_ptr().write(value);
+
+            let coerced: *mut T = coerce(&mut *ptr.as_ptr());
+            assert_eq!(coerced as *mut u8, ptr.as_ptr() as *mut u8);
+            assert_eq!(mem::size_of_val(&*coerced), mem::size_of::<U>());
+            NonNull::new_unchecked(coerced)
+        });
+
+        Self::from_elem(elem)
+    }
+}
+
+impl<T: Clone, R: Reclaim, N: Unsigned> Owned<[T], R, N> {
+    /// Allocates a new record containing a clone of all elements in `slice`.
+    #[inline]
+    pub fn from_slice(slice: &[T]) -> Self {
+        Self::from_slice_in(slice, Global)
+    }
+
+    /// Allocates a new record containing a clone of all elements in `slice`
+    /// with the given `alloc`.
+    #[inline]
+    pub fn from_slice_in(slice: &[T], alloc: impl Allocator + Send + 'static) -> Self {
+        let elem = alloc_unsized::<[T], R, _>(Layout::for_value(slice), alloc, |ptr| unsafe {
+            let data = ptr.cast::<T>().as_ptr();
+            for (idx, elem) in slice.iter().enumerate() {
+                data.add(idx).write(elem.clone());
+            }
+
+            NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(data, slice.len()))
+        });
+
+        Self::from_elem(elem)
+    }
+}
+
+impl<T, R: Reclaim, N: Unsigned> Owned<[T], R, N> {
+    /// Allocates a new record containing all elements of `vec`.
+    #[inline]
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        Self::from_vec_in(vec, Global)
+    }
+
+    /// Allocates a new record containing all elements of `vec` with the given
+    /// `alloc`.
+    #[inline]
+    pub fn from_vec_in(mut vec: Vec<T>, alloc: impl Allocator + Send + 'static) -> Self {
+        let layout = Layout::for_value(vec.as_slice());
+        let elem = alloc_unsized::<[T], R, _>(layout, alloc, |ptr| unsafe {
+            let data = ptr.cast::<T>().as_ptr();
+            ptr::copy_nonoverlapping(vec.as_ptr(), data, vec.len());
+            let len = vec.len();
+            vec.set_len(0);
+
+            NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(data, len))
+        });
+
+        Self::from_elem(elem)
+    }
+}
+
+impl<R: Reclaim, N: Unsigned> Owned<str, R, N> {
+    /// Allocates a new record containing a copy of `string`.
+    #[inline]
+    pub fn from_str_slice(string: &str) -> Self {
+        Self::from_str_slice_in(string, Global)
+    }
+
+    /// Allocates a new record containing a copy of `string` with the given
+    /// `alloc`.
+    #[inline]
+    pub fn from_str_slice_in(string: &str, alloc: impl Allocator + Send + 'static) -> Self {
+        let elem = alloc_unsized::<str, R, _>(Layout::for_value(string), alloc, |ptr| unsafe {
+            ptr::copy_nonoverlapping(string.as_ptr(), ptr.as_ptr(), string.len());
+            let bytes = ptr::slice_from_raw_parts_mut(ptr.as_ptr(), string.len());
+
+            NonNull::new_unchecked(bytes as *mut str)
+        });
+
+        Self::from_elem(elem)
+    }
+}
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Owned<T, R, N> {
+    /// Creates a new `Owned` from a pointer to the element of a newly
+    /// allocated record.
+    #[inline]
+    fn from_elem(elem: NonNull<T::Elem>) -> Self {
+        Self { inner: MarkedNonNull::<T::Elem, N>::from(elem).cast(), _marker: PhantomData }
+    }
+}
+
+/********** impl From *****************************************************************************/
+
+impl<T: Clone, R: Reclaim, N: Unsigned> From<&[T]> for Owned<[T], R, N> {
+    #[inline]
+    fn from(slice: &[T]) -> Self {
+        Self::from_slice(slice)
+    }
+}
+
+impl<T, R: Reclaim, N: Unsigned> From<Vec<T>> for Owned<[T], R, N> {
+    #[inline]
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec(vec)
+    }
+}
+
+impl<R: Reclaim, N: Unsigned> From<&str> for Owned<str, R, N> {
+    #[inline]
+    fn from(string: &str) -> Self {
+        Self::from_str_slice(string)
+    }
+}
+
+impl<R: Reclaim, N: Unsigned> From<String> for Owned<str, R, N> {
+    #[inline]
+    fn from(string: String) -> Self {
+        Self::from_str_slice(&string)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// UnsizedAllocation
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The memory block allocated for records of dynamically sized types, whose
+/// values are stored inline behind it.
+///
+/// Like the [`Allocation`][crate::allocator::Allocation] of regular records,
+/// the block is prefixed with the function for freeing it.
+#[repr(C)]
+struct UnsizedAllocation<T: ?Sized, R: Reclaim, A> {
+    record: Record<Unsized<T>, R>,
+    layout: Layout,
+    alloc: A,
+}
+
+/********** helper functions **********************************************************************/
+
+/// Allocates a record for a value with the given `layout` with the given
+/// `alloc` and returns a pointer to the record's element.
+///
+/// The value is initialized by `init`, which receives the pointer to the
+/// memory behind the record and returns the wide pointer to the value.
+#[inline]
+fn alloc_unsized<T: ?Sized, R: Reclaim, A: Allocator + Send + 'static>(
+    layout: Layout,
+    alloc: A,
+    init: impl FnOnce(NonNull<u8>) -> NonNull<T>,
+) -> NonNull<Unsized<T>> {
+    let (layout, offset) =
+        Layout::new::<UnsizedAllocation<T, R, A>>().extend(layout).expect("value too large");
+    let (layout, prefix) = allocator::prefixed(layout.pad_to_align());
+
+    let ptr = match alloc.allocate(layout) {
+        Some(ptr) => ptr,
+        None => handle_alloc_error(layout),
+    };
+
+    unsafe {
+        let allocation = ptr.as_ptr().add(prefix);
+        // if `init` panics, the allocation is leaked, but nothing is dropped
+        let value = init(NonNull::new_unchecked(allocation.add(offset)));
+
+        let allocation = allocation as *mut UnsizedAllocation<T, R, A>;
+        allocation.write(UnsizedAllocation {
+            record: Record::new(Unsized { ptr: value }),
+            layout,
+            alloc,
+        });
+
+        let record = NonNull::new_unchecked(allocation).cast();
+        allocator::write_release_fn(record, release_unsized::<T, R, A>);
+        NonNull::new_unchecked(&mut (*allocation).record.elem)
+    }
+}
+
+/// Releases the allocation of the record at `ptr` with its stored allocator.
+///
+/// Dropping the record also drops the value stored behind it.
+unsafe fn release_unsized<T: ?Sized, R: Reclaim, A: Allocator>(ptr: NonNull<()>, release: Release) {
+    let allocation = ptr.cast::<UnsizedAllocation<T, R, A>>().as_ptr();
+    release.drop_record(&mut (*allocation).record);
+
+    let layout = (*allocation).layout;
+    let alloc = ptr::read(&(*allocation).alloc);
+    // the prefix only depends on the alignment, which is the same for the entire block
+    let block = (ptr.as_ptr() as *mut u8).sub(allocator::prefixed(layout).1);
+    alloc.deallocate(NonNull::new_unchecked(block), layout);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U2;
+
+    use crate::leak::Leaking;
+    use crate::pointer::MarkedNonNullable;
+    use crate::retired::Retired;
+    use crate::test_util::DropCount;
+
+    type Atomic<T> = crate::Atomic<T, Leaking, U2>;
+    type Owned<T> = crate::Owned<T, Leaking, U2>;
+    type Shared<'g, T> = crate::Shared<'g, T, Leaking, U2>;
+
+    trait Count {
+        fn count(&self) -> usize;
+    }
+
+    impl_pointee!(dyn Count);
+
+    impl Count for DropCount {
+        fn count(&self) -> usize {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn slice() {
+        let mut owned = Owned::<[u32]>::from(vec![1, 2, 3]);
+        owned[0] = 0;
+
+        let atomic: Atomic<[u32]> = Atomic::from(Owned::compose(owned, 0b11));
+        let shared = atomic.load_shared(Ordering::Relaxed).unwrap();
+        let (slice, tag) = Shared::decompose_ref(shared);
+        assert_eq!(tag, 0b11);
+        assert_eq!(slice, &[0, 2, 3]);
+    }
+
+    #[test]
+    fn str() {
+        let atomic: Atomic<str> = Atomic::from(Owned::from("variable length key"));
+        let shared = atomic.load_shared(Ordering::Relaxed).unwrap();
+        assert_eq!(&*shared, "variable length key");
+    }
+
+    #[test]
+    fn trait_object() {
+        let count = Arc::new(AtomicUsize::new(3));
+        let owned = Owned::<dyn Count>::new_unsize(DropCount(Arc::clone(&count)), |elem| elem);
+        let atomic: Atomic<dyn Count> = Atomic::from(owned);
+        assert_eq!(atomic.load_shared(Ordering::Relaxed).unwrap().count(), 3);
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        unsafe { unlinked.retire() }; // leaks memory
+
+        let any = Owned::<dyn Any>::new_unsize(String::from("any"), |string| string);
+        assert_eq!(any.downcast_ref::<String>().map(String::as_str), Some("any"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_unsize_partial() {
+        let _ = Owned::<[u32]>::new_unsize(([1u32, 2], [3u32]), |pair| &mut pair.1);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let elems = vec![DropCount(Arc::clone(&count)); 4];
+        let owned = Owned::<[_]>::from_slice(&elems);
+        drop(elems);
+        assert_eq!(count.load(Ordering::Relaxed), 4);
+
+        let atomic: Atomic<[_]> = Atomic::from(owned);
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        assert_eq!(unlinked.len(), 4);
+        let elem = unlinked.into_marked_non_null().decompose_non_null();
+        let mut retired = unsafe { Retired::<Leaking>::new_unchecked(elem) };
+        unsafe { retired.reclaim() };
+        assert_eq!(count.load(Ordering::Relaxed), 8);
+
+        let mut atomic: Atomic<[_]> =
+            Atomic::from(Owned::from(vec![DropCount(Arc::clone(&count)); 4]));
+        drop(atomic.take());
+        assert_eq!(count.load(Ordering::Relaxed), 12);
+
+        let owned = Owned::<dyn Count>::new_unsize(DropCount(Arc::clone(&count)), |elem| elem);
+        drop(owned);
+        assert_eq!(count.load(Ordering::Relaxed), 13);
+    }
+}
@@ -0,0 +1,224 @@
+//! The global state of the epoch based reclamation scheme, which is shared by
+//! all threads.
+
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::registry::{Entry, Registry};
+use crate::retired::Retired;
+
+use super::Epoch;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global epoch counter, the registry of all participating threads and
+/// the records that were left behind by exiting threads.
+#[derive(Debug)]
+pub(crate) struct Global {
+    epoch: AtomicUsize,
+    participants: Registry<Participant>,
+    abandoned: Mutex<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state starting at epoch zero.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            epoch: AtomicUsize::new(0),
+            participants: Registry::new(),
+            abandoned: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Loads the current global epoch.
+    #[inline]
+    pub fn current_epoch(&self, order: Ordering) -> usize {
+        self.epoch.load(order)
+    }
+
+    /// Registers a new participant, which is initially not pinned.
+    #[inline]
+    pub fn register(&self) -> &Entry<Participant> {
+        self.participants.acquire()
+    }
+
+    /// Attempts to advance the global epoch and returns the (possibly
+    /// updated) current epoch.
+    ///
+    /// The epoch can only be advanced if all currently pinned participants
+    /// have observed the current epoch.
+    #[inline]
+    pub fn try_advance(&self) -> usize {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        atomic::fence(Ordering::SeqCst);
+
+        for participant in self.participants.iter() {
+            if let Some(pinned) = participant.pinned_epoch(Ordering::Relaxed) {
+                if pinned != epoch {
+                    return epoch;
+                }
+            }
+        }
+
+        atomic::fence(Ordering::Acquire);
+        match self.epoch.compare_exchange(epoch, epoch + 1, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => epoch + 1,
+            Err(actual) => actual,
+        }
+    }
+
+    /// Hands over all records in `records` to the global state, from where
+    /// they can eventually be reclaimed by any other thread.
+    #[inline]
+    pub fn abandon(&self, records: impl IntoIterator<Item = Sealed>) {
+        let mut abandoned = self.abandoned.lock().unwrap_or_else(|err| err.into_inner());
+        abandoned.extend(records);
+    }
+
+    /// Reclaims all abandoned records that have expired in the given `epoch`.
+    ///
+    /// If another thread is currently accessing the abandoned records, this is
+    /// a no-op.
+    #[inline]
+    pub fn reclaim_abandoned(&self, epoch: usize) {
+        let expired = match self.abandoned.try_lock() {
+            Ok(mut abandoned) => Sealed::split_expired(&mut abandoned, epoch),
+            Err(_) => return,
+        };
+
+        for sealed in expired {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Global {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline]
+    fn drop(&mut self) {
+        let abandoned = self.abandoned.get_mut().unwrap_or_else(|err| err.into_inner());
+        for sealed in abandoned.drain(..) {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Participant
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The globally visible state of a participating thread.
+#[derive(Debug, Default)]
+pub(crate) struct Participant {
+    state: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Participant {
+    const PINNED_BIT: usize = 0b1;
+
+    /// Announces the current global epoch and marks the participant as pinned.
+    #[inline]
+    pub fn pin(&self, global: &Global) {
+        let mut epoch = global.current_epoch(Ordering::Relaxed);
+        loop {
+            self.state.store((epoch << 1) | Self::PINNED_BIT, Ordering::Relaxed);
+            atomic::fence(Ordering::SeqCst);
+
+            // the global epoch may have been advanced before the announcement became visible
+            match global.current_epoch(Ordering::Relaxed) {
+                current if current == epoch => return,
+                current => epoch = current,
+            }
+        }
+    }
+
+    /// Marks the participant as no longer pinned.
+    #[inline]
+    pub fn unpin(&self) {
+        self.state.store(0, Ordering::Release);
+    }
+
+    /// Returns the announced epoch if the participant is currently pinned.
+    #[inline]
+    pub fn pinned_epoch(&self, order: Ordering) -> Option<usize> {
+        match self.state.load(order) {
+            state if state & Self::PINNED_BIT != 0 => Some(state >> 1),
+            _ => None,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Sealed
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record sealed with the global epoch at the time of its
+/// retirement.
+#[derive(Debug)]
+pub(crate) struct Sealed {
+    epoch: usize,
+    record: Retired<Epoch>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Sealed {
+    /// Creates a new sealed record.
+    #[inline]
+    pub fn new(record: Retired<Epoch>, epoch: usize) -> Self {
+        Self { epoch, record }
+    }
+
+    /// Returns `true` if the record can no longer be referenced by any thread
+    /// in the given `epoch`.
+    ///
+    /// Any thread that could have acquired a reference to the record must have
+    /// been pinned in the sealed epoch or the one before it.
+    /// Once the global epoch has advanced twice, all of these threads must have
+    /// become unpinned at least once.
+    #[inline]
+    pub fn is_expired(&self, epoch: usize) -> bool {
+        epoch.wrapping_sub(self.epoch) >= 2
+    }
+
+    /// Reclaims the sealed record.
+    ///
+    /// # Safety
+    ///
+    /// The record must be expired and must not be reclaimed more than once.
+    #[inline]
+    pub unsafe fn reclaim(mut self) {
+        self.record.reclaim();
+    }
+
+    /// Removes all records that have expired in the given `epoch` from
+    /// `records` and returns them.
+    #[inline]
+    pub fn split_expired(records: &mut Vec<Sealed>, epoch: usize) -> Vec<Sealed> {
+        let (expired, remaining) = records.drain(..).partition(|sealed| sealed.is_expired(epoch));
+        *records = remaining;
+        expired
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Sealed {}
@@ -0,0 +1,145 @@
+//! The thread local state of the epoch based reclamation scheme.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::sync::atomic::{self, Ordering};
+
+use crate::registry::Entry;
+use crate::retired::Retired;
+
+use super::global::{Global, Participant, Sealed};
+use super::Epoch;
+
+/// The number of records a thread retires before it attempts to advance the
+/// global epoch and reclaim its expired records (must be a power of two).
+const RECLAIM_THRESHOLD: usize = 128;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`Epoch`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first creates a
+/// [`Guard`][super::Guard] or retires a record through the [`GlobalReclaim`]
+/// interface.
+/// The instance of the current thread can be accessed through
+/// [`Epoch::with_local`][super::Epoch::with_local].
+///
+/// [`GlobalReclaim`]: crate::GlobalReclaim
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    participant: &'static Entry<Participant>,
+    guard_count: Cell<usize>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state and registers it as participant in the
+    /// global state.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        Self {
+            global,
+            participant: global.register(),
+            guard_count: Cell::new(0),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns `true` if the thread is currently pinned by at least one
+    /// guard.
+    #[inline]
+    pub fn is_pinned(&self) -> bool {
+        self.guard_count.get() > 0
+    }
+
+    /// Increments the guard count and pins the thread, if it is not already
+    /// pinned.
+    #[inline]
+    pub(crate) fn pin(&self) {
+        let count = self.guard_count.get();
+        self.guard_count.set(count.checked_add(1).expect("guard count overflow"));
+
+        if count == 0 {
+            self.participant.pin(self.global);
+        }
+    }
+
+    /// Decrements the guard count and unpins the thread, if there are no
+    /// more guards left.
+    #[inline]
+    pub(crate) fn unpin(&self) {
+        let count = self.guard_count.get() - 1;
+        self.guard_count.set(count);
+
+        if count == 0 {
+            self.participant.unpin();
+        }
+    }
+
+    /// Seals all given `records` with the current global epoch and stores
+    /// them until they expire.
+    ///
+    /// The global epoch is only loaded once for all records.
+    #[inline]
+    pub(crate) fn retire_records(&self, records: impl IntoIterator<Item = Retired<Epoch>>) {
+        // the epoch must be loaded *after* the records have been unlinked
+        atomic::fence(Ordering::SeqCst);
+        let epoch = self.global.current_epoch(Ordering::Relaxed);
+
+        // the records are collected first, because the iterator may itself retire records
+        let mut sealed: Vec<_> =
+            records.into_iter().map(|record| Sealed::new(record, epoch)).collect();
+        let retired = sealed.len();
+        self.retired.borrow_mut().append(&mut sealed);
+
+        let prev = self.retire_count.get();
+        let count = prev.wrapping_add(retired);
+        self.retire_count.set(count);
+        // reclaim whenever the count passes a multiple of the threshold
+        if count / RECLAIM_THRESHOLD != prev / RECLAIM_THRESHOLD {
+            self.try_reclaim();
+        }
+    }
+
+    /// Attempts to advance the global epoch and reclaims all expired records
+    /// retired by this thread or abandoned by exited threads.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        let epoch = self.global.try_advance();
+
+        // the records are taken out, because dropping them may retire further records
+        let expired = Sealed::split_expired(&mut self.retired.borrow_mut(), epoch);
+        for sealed in expired {
+            unsafe { sealed.reclaim() };
+        }
+
+        self.global.reclaim_abandoned(epoch);
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        if self.is_pinned() {
+            self.participant.unpin();
+        }
+
+        self.try_reclaim();
+        self.participant.release();
+
+        let retired = mem::take(self.retired.get_mut());
+        if !retired.is_empty() {
+            self.global.abandon(retired);
+        }
+    }
+}
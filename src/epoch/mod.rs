@@ -0,0 +1,420 @@
+//! An epoch based memory reclamation scheme.
+//!
+//! All threads share a global epoch counter.
+//! Creating a [`Guard`] *pins* the current thread, which announces the global
+//! epoch it has observed.
+//! While at least one guard exists, all values loaded by the thread are
+//! protected from reclamation.
+//! Retired records are stored in a thread local bag alongside the global
+//! epoch at the time of their retirement.
+//! The global epoch can only be advanced once all pinned threads have
+//! observed the current epoch and records can be reclaimed once the global
+//! epoch has been advanced twice since their retirement.
+//!
+//! Note, that a single thread that remains pinned indefinitely prevents any
+//! further reclamation by any thread.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::epoch::{Epoch, Guard, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::epoch::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed before `guard` is dropped
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! assert_eq!(*shared, 1);
+//! drop(guard);
+//!
+//! Epoch::try_reclaim();
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+
+pub use self::local::Local;
+
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::retired::Retired;
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, ProtectRegion, Reclaim};
+
+use self::global::{Global, Sealed};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Epoch`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Epoch, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Epoch`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Epoch, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Epoch`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Epoch, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Epoch`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, Epoch, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Epoch`] reclamation
+/// scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Epoch, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Epoch`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Epoch, N>;
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Epoch
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An epoch based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct Epoch;
+
+/********** impl inherent *************************************************************************/
+
+impl Epoch {
+    /// Returns `true` if the current thread is pinned by at least one
+    /// [`Guard`].
+    #[inline]
+    pub fn is_pinned() -> bool {
+        LOCAL.with(Local::is_pinned)
+    }
+
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Converts an `unlinked` record into a type-erased [`Retired`] record.
+    #[inline]
+    unsafe fn into_retired<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> Retired<Self> {
+        Retired::new_unchecked(
+            MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null(),
+        )
+    }
+
+    /// Retires the `records` in the local state of the current thread or, if
+    /// the thread local storage has already been destroyed, directly abandons
+    /// them to the global state.
+    #[inline]
+    fn retire_records(records: impl IntoIterator<Item = Retired<Self>>) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => unsafe { (*local).retire_records(records) },
+            Err(_) => {
+                let epoch = GLOBAL.current_epoch(Ordering::SeqCst);
+                GLOBAL.abandon(records.into_iter().map(|record| Sealed::new(record, epoch)));
+            }
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Epoch {
+    type Guard = Guard;
+
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_records(Some(Self::into_retired(unlinked)));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_records(Some(Self::into_retired(unlinked)));
+    }
+
+    /// Retires all records in `batch`, sealing them all with the same epoch.
+    #[inline]
+    unsafe fn retire_batch<T: 'static, N: Unsigned>(
+        batch: impl IntoIterator<Item = Unlinked<T, N>>,
+    ) {
+        Self::retire_records(batch.into_iter().map(|unlinked| Self::into_retired(unlinked)));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Epoch {
+    type Local = Local;
+    type RecordHeader = ();
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_records(Some(Self::into_retired(unlinked)));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_records(Some(Self::into_retired(unlinked)));
+    }
+
+    /// Retires all records in `batch`, sealing them all with the same epoch.
+    #[inline]
+    unsafe fn retire_local_batch<T: 'static, N: Unsigned>(
+        local: &Local,
+        batch: impl IntoIterator<Item = Unlinked<T, N>>,
+    ) {
+        local.retire_records(batch.into_iter().map(|unlinked| Self::into_retired(unlinked)));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Epoch`] reclamation
+/// scheme.
+///
+/// A guard pins the thread it is created in for as long as it exists and
+/// protects all values loaded during this time.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard and pins the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| {
+            local.pin();
+            Self { local: NonNull::from(local) }
+        })
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    #[inline]
+    fn clone(&self) -> Self {
+        unsafe { self.local.as_ref().pin() };
+        Self { local: self.local }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().unpin() };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Epoch;
+
+    /// This is a no-op, since the guard protects all values as long as it
+    /// exists.
+    #[inline]
+    fn release(&mut self) {}
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(atomic.load_raw(order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        match atomic.load_raw(order) {
+            raw if raw == expected => Ok(unsafe { Marked::from_marked_ptr(raw) }),
+            _ => Err(NotEqualError),
+        }
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl ProtectRegion for Guard {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Epoch, Guard};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    fn reclaim_until(count: &AtomicUsize, expected: usize) {
+        test_util::reclaim_until(count, expected, Epoch::try_reclaim);
+    }
+
+    #[test]
+    fn pin_nested() {
+        assert!(!Epoch::is_pinned());
+        let guard = Guard::new();
+        let clone = guard.clone();
+        drop(guard);
+        assert!(Epoch::is_pinned());
+        drop(clone);
+        assert!(!Epoch::is_pinned());
+    }
+
+    #[test]
+    fn reclaim_after_unpin() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let guard = Guard::new();
+        let shared = atomic.load(Ordering::Relaxed, &guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        unsafe { unlinked.retire() };
+
+        for _ in 0..10 {
+            Epoch::try_reclaim();
+        }
+
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        drop(guard);
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn retire_local_batch() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let batch: Vec<_> = (0..3)
+            .map(|_| Atomic::new(DropCount(Arc::clone(&count))))
+            .map(|atomic| atomic.swap(Owned::none(), Ordering::Relaxed).unwrap())
+            .collect();
+
+        Epoch::with_local(|local| unsafe { Epoch::retire_local_batch(local, batch) });
+        reclaim_until(&count, 3);
+    }
+
+    #[test]
+    fn retire_batch() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomics: Vec<_> =
+            (0..200).map(|_| Atomic::new(DropCount(Arc::clone(&count)))).collect();
+
+        let guard = Guard::new();
+        let unlinked =
+            atomics.iter().filter_map(|atomic| atomic.swap(Owned::none(), Ordering::Relaxed));
+        unsafe { Epoch::retire_batch(unlinked) };
+
+        for _ in 0..10 {
+            Epoch::try_reclaim();
+        }
+
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        drop(guard);
+        reclaim_until(&count, 200);
+    }
+
+    #[test]
+    fn retire_batch_reentrant() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomics: Vec<_> = (0..4).map(|_| Atomic::new(DropCount(Arc::clone(&count)))).collect();
+
+        // the batch iterator itself retires further records on the same thread
+        let unlinked = atomics.iter().filter_map(|atomic| {
+            let inner = Atomic::new(DropCount(Arc::clone(&count)));
+            unsafe { inner.swap(Owned::none(), Ordering::Relaxed).unwrap().retire() };
+            atomic.swap(Owned::none(), Ordering::Relaxed)
+        });
+        unsafe { Epoch::retire_batch(unlinked) };
+
+        reclaim_until(&count, 8);
+    }
+
+    #[test]
+    fn defer() {
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let guard = Guard::new();
+        let deferred = Arc::clone(&count);
+        Epoch::defer(move || {
+            deferred.fetch_add(1, Ordering::Relaxed);
+        });
+
+        for _ in 0..10 {
+            Epoch::try_reclaim();
+        }
+
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        drop(guard);
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn reclaim_abandoned() {
+        let count = test_util::retire_from_threads::<Epoch>(4, 100);
+        reclaim_until(&count, 400);
+    }
+}
@@ -0,0 +1,283 @@
+//! Reclamation domains for the hazard pointer reclamation scheme, which own
+//! their own independent global state.
+
+use std::mem;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use typenum::Unsigned;
+
+use crate::pointer::{Marked, MarkedPointer, MarkedPtr};
+use crate::registry::Entry;
+use crate::{AcquireResult, Protect, ReclaimDomain};
+
+use super::global::{Global, HazardPtr};
+use super::{Atomic, Hazard, Shared, Unlinked};
+
+/// The number of records retired into a domain before it scans all of its
+/// hazard pointers and reclaims all unprotected records (must be a power of
+/// two).
+const SCAN_THRESHOLD: usize = 128;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Domain
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A hazard pointer reclamation domain with its own set of hazard pointers
+/// and retired records.
+///
+/// Records retired into a domain are only checked against the hazard
+/// pointers of guards created from the same domain, so the domain is
+/// isolated from all other domains and from the global [`Hazard`] state.
+/// All records retired into a domain are reclaimed at the latest, once the
+/// domain and all of its guards have been dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::Ordering;
+///
+/// use reclaim::hazard::{Domain, Owned};
+/// use reclaim::prelude::*;
+///
+/// type Atomic<T> = reclaim::hazard::Atomic<T, reclaim::typenum::U0>;
+///
+/// let domain = Domain::new();
+/// let atomic = Atomic::new(1);
+///
+/// let mut guard = domain.guard();
+/// let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+///
+/// if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+///     // the record must be retired into the domain whose guards protect it
+///     unsafe { unlinked.retire_in(&domain) };
+/// }
+///
+/// domain.try_reclaim();
+/// assert_eq!(*shared, 1);
+/// # let mut atomic = atomic;
+/// # drop(atomic.take());
+/// ```
+#[derive(Debug, Default)]
+pub struct Domain {
+    global: Arc<Global>,
+    retire_count: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Domain {
+    /// Creates a new empty domain.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the given `record` in the domain and scans all of the domain's
+    /// hazard pointers, if enough records have been retired.
+    #[inline]
+    fn retire_record<T, N: Unsigned>(&self, unlinked: Unlinked<T, N>) {
+        let record = unsafe { Hazard::into_retired(unlinked) };
+        self.global.abandon(Some(record));
+
+        let count = self.retire_count.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+        if count & (SCAN_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+}
+
+/********** impl ReclaimDomain ********************************************************************/
+
+unsafe impl ReclaimDomain for Domain {
+    type Reclaimer = Hazard;
+    type Guard = DomainGuard;
+
+    #[inline]
+    fn guard(&self) -> DomainGuard {
+        let global = Arc::clone(&self.global);
+        let hazard = NonNull::from(global.acquire_hazard());
+        DomainGuard { hazard, global }
+    }
+
+    /// Scans all of the domain's hazard pointers and reclaims all unprotected
+    /// records retired into the domain.
+    ///
+    /// If another thread is currently reclaiming records of the same domain,
+    /// this is a no-op.
+    #[inline]
+    fn try_reclaim(&self) {
+        let protected = self.global.collect_protected();
+        self.global.reclaim_abandoned(&protected);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(&self, unlinked: Unlinked<T, N>) {
+        self.retire_record(unlinked);
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(&self, unlinked: Unlinked<T, N>) {
+        self.retire_record(unlinked);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// DomainGuard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The guard type for hazard pointer reclamation [`Domain`]s.
+///
+/// Each guard owns a hazard pointer of its domain for as long as it exists
+/// and can protect a single value at a time.
+/// A guard keeps its domain's global state alive, even if the [`Domain`]
+/// itself is dropped.
+#[derive(Debug)]
+pub struct DomainGuard {
+    hazard: NonNull<Entry<HazardPtr>>,
+    global: Arc<Global>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl DomainGuard {
+    #[inline]
+    fn hazard(&self) -> &Entry<HazardPtr> {
+        // the hazard pointer lives as long as the domain's global state
+        unsafe { self.hazard.as_ref() }
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for DomainGuard {
+    /// Creates a new guard with its own hazard pointer of the same domain,
+    /// which protects the same value as `self`.
+    #[inline]
+    fn clone(&self) -> Self {
+        let global = Arc::clone(&self.global);
+        let hazard = global.acquire_hazard();
+        // the value is still protected by `self`, so no validation is required
+        if let Some(protected) = self.hazard().protected(Ordering::Relaxed) {
+            hazard.protect(protected);
+        }
+
+        Self { hazard: NonNull::from(hazard), global }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for DomainGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let hazard = self.hazard();
+        hazard.clear();
+        hazard.release();
+    }
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+// unlike hazard pointers acquired through the thread local state, domain hazard pointers are not
+// bound to any thread
+unsafe impl Send for DomainGuard {}
+unsafe impl Sync for DomainGuard {}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for DomainGuard {
+    type Reclaimer = Hazard;
+
+    #[inline]
+    fn release(&mut self) {
+        self.hazard().clear();
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(super::protect(self.hazard(), atomic, order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Hazard, N> {
+        // the value is published in a second hazard pointer, so that the previously protected
+        // value remains protected if the validation fails
+        let hazard = NonNull::from(self.global.acquire_hazard());
+        let res = super::protect_if_equal(unsafe { hazard.as_ref() }, atomic, expected, order);
+        let unused = match res {
+            Ok(_) => mem::replace(&mut self.hazard, hazard),
+            Err(_) => hazard,
+        };
+
+        let unused = unsafe { unused.as_ref() };
+        unused.clear();
+        unused.release();
+
+        res.map(|marked| unsafe { Marked::from_marked_ptr(marked) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::DropCount;
+
+    use super::Domain;
+
+    type Atomic<T> = crate::hazard::Atomic<T, U0>;
+    type Owned<T> = crate::hazard::Owned<T, U0>;
+
+    #[test]
+    fn protected_in_domain() {
+        let domain = Domain::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = domain.guard();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire_in(&domain) };
+
+        domain.try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        guard.release();
+        domain.try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn reclaim_on_drop() {
+        let domain = Domain::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = domain.guard();
+        let _ = atomic.load(Ordering::Acquire, &mut guard);
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire_in(&domain) };
+
+        // the guard keeps the domain's state alive
+        drop(domain);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        drop(guard);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+}
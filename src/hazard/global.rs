@@ -0,0 +1,175 @@
+//! The global state of the hazard pointer reclamation scheme, which is shared
+//! by all threads.
+
+use std::ptr;
+use std::sync::atomic::{self, AtomicPtr, Ordering};
+use std::sync::Mutex;
+
+use crate::registry::{Entry, Registry};
+use crate::retired::Retired;
+
+use super::Hazard;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The registry of all hazard pointers and the records that were left behind
+/// by exiting threads.
+#[derive(Debug)]
+pub(crate) struct Global {
+    hazards: Registry<HazardPtr>,
+    abandoned: Mutex<Abandoned>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state without any hazard pointers.
+    #[inline]
+    pub fn new() -> Self {
+        Self { hazards: Registry::new(), abandoned: Mutex::new(Abandoned(Vec::new())) }
+    }
+
+    /// Acquires an unused hazard pointer for exclusive use by the calling
+    /// thread.
+    #[inline]
+    pub fn acquire_hazard(&self) -> &Entry<HazardPtr> {
+        self.hazards.acquire()
+    }
+
+    /// Collects the addresses of all records that are currently protected by
+    /// any hazard pointer into a sorted vector.
+    #[inline]
+    pub fn collect_protected(&self) -> Vec<*const ()> {
+        // synchronizes with the fence in `HazardPtr::protect`
+        atomic::fence(Ordering::SeqCst);
+
+        let mut protected: Vec<_> =
+            self.hazards.iter().filter_map(|hazard| hazard.protected(Ordering::Relaxed)).collect();
+        protected.sort_unstable();
+        protected
+    }
+
+    /// Hands over all records in `records` to the global state, from where
+    /// they can eventually be reclaimed by any other thread.
+    #[inline]
+    pub fn abandon(&self, records: impl IntoIterator<Item = Retired<Hazard>>) {
+        let mut abandoned = self.abandoned.lock().unwrap_or_else(|err| err.into_inner());
+        abandoned.0.extend(records);
+    }
+
+    /// Reclaims all abandoned records that are not contained in the (sorted)
+    /// slice of `protected` addresses.
+    ///
+    /// If another thread is currently accessing the abandoned records, this is
+    /// a no-op.
+    #[inline]
+    pub fn reclaim_abandoned(&self, protected: &[*const ()]) {
+        let unprotected = match self.abandoned.try_lock() {
+            Ok(mut abandoned) => split_unprotected(&mut abandoned.0, protected),
+            Err(_) => return,
+        };
+
+        for mut retired in unprotected {
+            unsafe { retired.reclaim() };
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Global {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline]
+    fn drop(&mut self) {
+        let abandoned = self.abandoned.get_mut().unwrap_or_else(|err| err.into_inner());
+        for mut retired in abandoned.0.drain(..) {
+            unsafe { retired.reclaim() };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// HazardPtr
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A globally visible pointer to a record, which must not be reclaimed as
+/// long as it is protected.
+#[derive(Debug)]
+pub(crate) struct HazardPtr {
+    protected: AtomicPtr<()>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl HazardPtr {
+    /// Publishes the given record address as protected.
+    ///
+    /// The protection is only established once the caller has validated
+    /// that the record has not been unlinked in the meantime.
+    #[inline]
+    pub fn protect(&self, record: *const ()) {
+        self.protected.store(record as *mut (), Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Clears any current protection.
+    #[inline]
+    pub fn clear(&self) {
+        self.protected.store(ptr::null_mut(), Ordering::Release);
+    }
+
+    /// Returns the currently protected record address, if there is any.
+    #[inline]
+    pub fn protected(&self, order: Ordering) -> Option<*const ()> {
+        match self.protected.load(order) {
+            ptr if ptr.is_null() => None,
+            ptr => Some(ptr as *const ()),
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for HazardPtr {
+    #[inline]
+    fn default() -> Self {
+        Self { protected: AtomicPtr::new(ptr::null_mut()) }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Abandoned
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+struct Abandoned(Vec<Retired<Hazard>>);
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Abandoned {}
+
+/********** helper functions **********************************************************************/
+
+/// Removes all records that are not contained in the (sorted) slice of
+/// `protected` addresses from `records` and returns them.
+#[inline]
+pub(crate) fn split_unprotected(
+    records: &mut Vec<Retired<Hazard>>,
+    protected: &[*const ()],
+) -> Vec<Retired<Hazard>> {
+    let (unprotected, remaining) =
+        records.drain(..).partition(|retired| protected.binary_search(&retired.as_ptr()).is_err());
+    *records = remaining;
+    unprotected
+}
@@ -0,0 +1,122 @@
+//! The thread local state of the hazard pointer reclamation scheme.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+
+use crate::registry::Entry;
+use crate::retired::Retired;
+
+use super::global::{self, Global, HazardPtr};
+use super::Hazard;
+
+/// The number of records a thread retires before it scans all hazard pointers
+/// and reclaims its unprotected records (must be a power of two).
+const SCAN_THRESHOLD: usize = 128;
+/// The maximum number of released hazard pointers each thread keeps cached
+/// for later re-use.
+const HAZARD_CACHE_SIZE: usize = 16;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`Hazard`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first creates a
+/// [`Guard`][super::Guard] or retires a record through the [`GlobalReclaim`]
+/// interface.
+/// The instance of the current thread can be accessed through
+/// [`Hazard::with_local`][super::Hazard::with_local].
+///
+/// [`GlobalReclaim`]: crate::GlobalReclaim
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    hazard_cache: RefCell<Vec<&'static Entry<HazardPtr>>>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Retired<Hazard>>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        Self {
+            global,
+            hazard_cache: RefCell::new(Vec::new()),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Acquires a hazard pointer, preferably from the thread local cache.
+    #[inline]
+    pub(crate) fn acquire_hazard(&self) -> &'static Entry<HazardPtr> {
+        match self.hazard_cache.borrow_mut().pop() {
+            Some(hazard) => hazard,
+            None => self.global.acquire_hazard(),
+        }
+    }
+
+    /// Clears and returns the `hazard` to the thread local cache or releases
+    /// it globally, if the cache is full.
+    #[inline]
+    pub(crate) fn release_hazard(&self, hazard: &'static Entry<HazardPtr>) {
+        hazard.clear();
+        let mut cache = self.hazard_cache.borrow_mut();
+        if cache.len() < HAZARD_CACHE_SIZE {
+            cache.push(hazard);
+        } else {
+            hazard.release();
+        }
+    }
+
+    /// Stores the given `record` until it is no longer protected by any
+    /// hazard pointer.
+    #[inline]
+    pub(crate) fn retire_record(&self, record: Retired<Hazard>) {
+        self.retired.borrow_mut().push(record);
+
+        let count = self.retire_count.get().wrapping_add(1);
+        self.retire_count.set(count);
+        if count & (SCAN_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+
+    /// Scans all hazard pointers and reclaims all unprotected records retired
+    /// by this thread or abandoned by exited threads.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        let protected = self.global.collect_protected();
+
+        // the records are taken out, because dropping them may retire further records
+        let unprotected = global::split_unprotected(&mut self.retired.borrow_mut(), &protected);
+        for mut retired in unprotected {
+            unsafe { retired.reclaim() };
+        }
+
+        self.global.reclaim_abandoned(&protected);
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        for hazard in self.hazard_cache.get_mut().drain(..) {
+            hazard.release();
+        }
+
+        self.try_reclaim();
+
+        let retired = mem::take(self.retired.get_mut());
+        if !retired.is_empty() {
+            self.global.abandon(retired);
+        }
+    }
+}
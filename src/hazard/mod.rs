@@ -0,0 +1,430 @@
+//! A hazard pointer based memory reclamation scheme.
+//!
+//! Every [`Guard`] owns a single *hazard pointer*, which is a globally
+//! visible slot for announcing the address of the record that is currently
+//! protected by this guard.
+//! Values are protected by first publishing their address and then
+//! validating that the value has not been changed (and hence possibly
+//! unlinked) in the meantime.
+//! Retired records are stored in thread local lists, which are periodically
+//! scanned against all published hazard pointers.
+//! Only records that are not protected by any hazard pointer are reclaimed.
+//!
+//! Since each guard can only protect a single value at a time, values can only
+//! be loaded with *mutable* references to guards.
+//! In exchange, the amount of memory that can not be reclaimed at any time is
+//! bounded, even if some threads stall indefinitely.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::hazard::{Guard, Hazard, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::hazard::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let mut guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed while `guard` protects it
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! Hazard::try_reclaim();
+//! assert_eq!(*shared, 1);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod domain;
+mod global;
+mod local;
+
+pub use self::domain::{Domain, DomainGuard};
+pub use self::local::Local;
+
+use core::mem;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::registry::Entry;
+use crate::retired::Retired;
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, Reclaim, Record};
+
+use self::global::{Global, HazardPtr};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Hazard`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Hazard, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Hazard`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Hazard, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Hazard`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Hazard, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Hazard`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, Hazard, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Hazard`] reclamation
+/// scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Hazard, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Hazard`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Hazard, N>;
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Hazard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A hazard pointer based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct Hazard;
+
+/********** impl inherent *************************************************************************/
+
+impl Hazard {
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Converts an `unlinked` record into a type-erased [`Retired`] record.
+    #[inline]
+    unsafe fn into_retired<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> Retired<Self> {
+        Retired::new_unchecked(
+            MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null(),
+        )
+    }
+
+    /// Retires the `record` in the local state of the current thread or, if
+    /// the thread local storage has already been destroyed, directly abandons
+    /// it to the global state.
+    #[inline]
+    fn retire_record(record: Retired<Self>) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => unsafe { (*local).retire_record(record) },
+            Err(_) => GLOBAL.abandon(Some(record)),
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Hazard {
+    type Guard = Guard;
+
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Hazard {
+    type Local = Local;
+    type RecordHeader = ();
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Hazard`] reclamation
+/// scheme.
+///
+/// Each guard owns a hazard pointer for as long as it exists and can protect
+/// a single value at a time.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+    hazard: &'static Entry<HazardPtr>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard owning a hazard pointer, which does not yet
+    /// protect any value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| Self { local: NonNull::from(local), hazard: local.acquire_hazard() })
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    /// Creates a new guard with its own hazard pointer, which protects the
+    /// same value as `self`.
+    #[inline]
+    fn clone(&self) -> Self {
+        let local = unsafe { self.local.as_ref() };
+        let hazard = local.acquire_hazard();
+        // the value is still protected by `self`, so no validation is required
+        if let Some(protected) = self.hazard.protected(Ordering::Relaxed) {
+            hazard.protect(protected);
+        }
+
+        Self { local: self.local, hazard }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().release_hazard(self.hazard) };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Hazard;
+
+    #[inline]
+    fn release(&mut self) {
+        self.hazard.clear();
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(protect(self.hazard, atomic, order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        // the value is published in a second hazard pointer, so that the previously protected
+        // value remains protected if the validation fails
+        let local = unsafe { self.local.as_ref() };
+        let hazard = local.acquire_hazard();
+        match protect_if_equal(hazard, atomic, expected, order) {
+            Ok(marked) => {
+                local.release_hazard(mem::replace(&mut self.hazard, hazard));
+                Ok(unsafe { Marked::from_marked_ptr(marked) })
+            }
+            Err(err) => {
+                local.release_hazard(hazard);
+                Err(err)
+            }
+        }
+    }
+}
+
+/********** helper functions **********************************************************************/
+
+/// Publishes the address of the record containing `ptr` in the `hazard`
+/// pointer.
+#[inline]
+fn publish<T>(hazard: &HazardPtr, ptr: *mut T) {
+    let record = unsafe { Record::<T, Hazard>::from_raw(ptr) };
+    hazard.protect(record.as_ptr() as *const ());
+}
+
+/// Loads a value from `atomic` and protects it with the `hazard` pointer.
+#[inline]
+fn protect<T, N: Unsigned>(
+    hazard: &HazardPtr,
+    atomic: &Atomic<T, N>,
+    order: Ordering,
+) -> MarkedPtr<T, N> {
+    let mut marked = atomic.load_raw(Ordering::Relaxed);
+    loop {
+        let ptr = match MarkedNonNull::new(marked) {
+            Marked::Value(ptr) => ptr,
+            Marked::Null(_) => {
+                hazard.clear();
+                return marked;
+            }
+        };
+
+        publish(hazard, ptr.decompose_ptr());
+
+        // the value may have been unlinked before the hazard pointer became visible
+        let current = atomic.load_raw(order);
+        if current.decompose_ptr() == ptr.decompose_ptr() {
+            return current;
+        }
+
+        marked = current;
+    }
+}
+
+/// Loads a value from `atomic` and protects it with the (unused) `hazard`
+/// pointer, if it equals `expected`.
+///
+/// On failure, the `hazard` pointer may still contain a published address.
+#[inline]
+fn protect_if_equal<T, N: Unsigned>(
+    hazard: &HazardPtr,
+    atomic: &Atomic<T, N>,
+    expected: MarkedPtr<T, N>,
+    order: Ordering,
+) -> Result<MarkedPtr<T, N>, NotEqualError> {
+    if atomic.load_raw(Ordering::Relaxed) != expected {
+        return Err(NotEqualError);
+    }
+
+    if expected.is_null() {
+        return Ok(expected);
+    }
+
+    publish(hazard, expected.decompose_ptr());
+    if atomic.load_raw(order) == expected {
+        Ok(expected)
+    } else {
+        Err(NotEqualError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, Hazard};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    #[test]
+    fn protected_not_reclaimed() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        Hazard::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        guard.release();
+        Hazard::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn protect_if_equal() {
+        let atomic = Atomic::new(1);
+        let mut guard = Guard::new();
+
+        let expected = atomic.load_raw(Ordering::Relaxed);
+        let shared = atomic.load_if_equal(expected, Ordering::Acquire, &mut guard);
+        assert_eq!(*shared.unwrap().unwrap(), 1);
+
+        let res = atomic.load_if_equal(Default::default(), Ordering::Acquire, &mut guard);
+        assert!(res.is_err());
+
+        let mut atomic = atomic;
+        drop(atomic.take());
+    }
+
+    #[test]
+    fn protect_if_equal_keeps_protection() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+        let mut other = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let _ = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        // a failed protection attempt must not release the previously protected value
+        let res = other.load_if_equal(Default::default(), Ordering::Acquire, &mut guard);
+        assert!(res.is_err());
+        Hazard::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        guard.release();
+        Hazard::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+
+        drop(other.take());
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn reclaim_abandoned() {
+        let count = test_util::retire_from_threads::<Hazard>(4, 100);
+        test_util::reclaim_until(&count, 400, Hazard::try_reclaim);
+    }
+}
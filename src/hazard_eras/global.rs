@@ -0,0 +1,247 @@
+//! The global state of the hazard eras reclamation scheme, which is shared by
+//! all threads.
+
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::registry::{Entry, Registry};
+use crate::retired::Retired;
+
+use super::HazardEras;
+
+/// The era value of a hazard era that does not protect any records.
+pub(crate) const NONE: usize = 0;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global era clock, the registry of all hazard eras and the records that
+/// were left behind by exiting threads.
+#[derive(Debug)]
+pub(crate) struct Global {
+    era: AtomicUsize,
+    hazards: Registry<HazardEra>,
+    abandoned: Mutex<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state without any hazard eras.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            era: AtomicUsize::new(NONE + 1),
+            hazards: Registry::new(),
+            abandoned: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Loads the current global era.
+    #[inline]
+    pub fn current_era(&self, order: Ordering) -> usize {
+        self.era.load(order)
+    }
+
+    /// Advances the global era, unless it has already been advanced since
+    /// `era` was loaded.
+    #[inline]
+    pub fn advance_era(&self, era: usize) {
+        let _ = self.era.compare_exchange(era, era + 1, Ordering::AcqRel, Ordering::Relaxed);
+    }
+
+    /// Acquires an unused hazard era for exclusive use by the calling thread.
+    #[inline]
+    pub fn acquire_hazard(&self) -> &Entry<HazardEra> {
+        self.hazards.acquire()
+    }
+
+    /// Collects all eras that are currently protected by any hazard era into
+    /// a sorted vector.
+    #[inline]
+    pub fn collect_protected(&self) -> Vec<usize> {
+        // synchronizes with the fence in `HazardEra::protect`
+        atomic::fence(Ordering::SeqCst);
+
+        let mut protected: Vec<_> =
+            self.hazards.iter().filter_map(|hazard| hazard.protected(Ordering::Relaxed)).collect();
+        protected.sort_unstable();
+        protected
+    }
+
+    /// Hands over all records in `records` to the global state, from where
+    /// they can eventually be reclaimed by any other thread.
+    #[inline]
+    pub fn abandon(&self, records: impl IntoIterator<Item = Sealed>) {
+        let mut abandoned = self.abandoned.lock().unwrap_or_else(|err| err.into_inner());
+        abandoned.extend(records);
+    }
+
+    /// Reclaims all abandoned records whose lifetimes do not intersect with
+    /// any of the (sorted) `protected` eras.
+    ///
+    /// If another thread is currently accessing the abandoned records, this is
+    /// a no-op.
+    #[inline]
+    pub fn reclaim_abandoned(&self, protected: &[usize]) {
+        let unprotected = match self.abandoned.try_lock() {
+            Ok(mut abandoned) => Sealed::split_unprotected(&mut abandoned, protected),
+            Err(_) => return,
+        };
+
+        for sealed in unprotected {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Global {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline]
+    fn drop(&mut self) {
+        let abandoned = self.abandoned.get_mut().unwrap_or_else(|err| err.into_inner());
+        for sealed in abandoned.drain(..) {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// HazardEra
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A globally visible era, in which all records that were alive at the time
+/// must not be reclaimed as long as it is protected.
+#[derive(Debug, Default)]
+pub(crate) struct HazardEra {
+    protected: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl HazardEra {
+    /// Publishes the given `era` as protected.
+    ///
+    /// The protection is only established once the caller has validated
+    /// that the global era has not been advanced in the meantime.
+    #[inline]
+    pub fn protect(&self, era: usize) {
+        self.protected.store(era, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Clears any current protection.
+    #[inline]
+    pub fn clear(&self) {
+        self.protected.store(NONE, Ordering::Release);
+    }
+
+    /// Returns the currently protected era, if there is any.
+    #[inline]
+    pub fn protected(&self, order: Ordering) -> Option<usize> {
+        match self.protected.load(order) {
+            NONE => None,
+            era => Some(era),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Sealed
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record sealed with the eras of its allocation and its
+/// retirement.
+#[derive(Debug)]
+pub(crate) struct Sealed {
+    birth_era: usize,
+    retire_era: usize,
+    record: Retired<HazardEras>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Sealed {
+    /// Creates a new sealed record.
+    #[inline]
+    pub fn new(record: Retired<HazardEras>, birth_era: usize, retire_era: usize) -> Self {
+        Self { birth_era, retire_era, record }
+    }
+
+    /// Returns `true` if any of the (sorted) `protected` eras lies within the
+    /// lifetime of the record.
+    #[inline]
+    pub fn is_protected(&self, protected: &[usize]) -> bool {
+        match protected.binary_search(&self.birth_era) {
+            Ok(_) => true,
+            Err(idx) => matches!(protected.get(idx), Some(&era) if era <= self.retire_era),
+        }
+    }
+
+    /// Reclaims the sealed record.
+    ///
+    /// # Safety
+    ///
+    /// The record must not be protected and must not be reclaimed more than
+    /// once.
+    #[inline]
+    pub unsafe fn reclaim(mut self) {
+        self.record.reclaim();
+    }
+
+    /// Removes all records whose lifetimes do not intersect with any of the
+    /// (sorted) `protected` eras from `records` and returns them.
+    #[inline]
+    pub fn split_unprotected(records: &mut Vec<Sealed>, protected: &[usize]) -> Vec<Sealed> {
+        let (unprotected, remaining) =
+            records.drain(..).partition(|sealed| !sealed.is_protected(protected));
+        *records = remaining;
+        unprotected
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Sealed {}
+
+#[cfg(test)]
+mod tests {
+    use typenum::U0;
+
+    use crate::pointer::MarkedNonNullable;
+    use crate::retired::Retired;
+
+    use super::super::Owned;
+    use super::Sealed;
+
+    #[test]
+    fn is_protected() {
+        let owned: Owned<i32, U0> = Owned::new(1);
+        let record = unsafe {
+            Retired::new_unchecked(
+                MarkedNonNullable::into_marked_non_null(owned).decompose_non_null(),
+            )
+        };
+        let sealed = Sealed::new(record, 3, 5);
+
+        assert!(!sealed.is_protected(&[]));
+        assert!(!sealed.is_protected(&[1, 2, 6]));
+        assert!(sealed.is_protected(&[1, 3]));
+        assert!(sealed.is_protected(&[4, 9]));
+        assert!(sealed.is_protected(&[5]));
+
+        unsafe { sealed.reclaim() };
+    }
+}
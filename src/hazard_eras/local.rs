@@ -0,0 +1,129 @@
+//! The thread local state of the hazard eras reclamation scheme.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::sync::atomic::{self, Ordering};
+
+use crate::registry::Entry;
+use crate::retired::Retired;
+
+use super::global::{Global, HazardEra, Sealed};
+use super::HazardEras;
+
+/// The number of records a thread retires before it scans all hazard eras
+/// and reclaims its unprotected records (must be a power of two).
+const SCAN_THRESHOLD: usize = 128;
+/// The maximum number of released hazard eras each thread keeps cached for
+/// later re-use.
+const HAZARD_CACHE_SIZE: usize = 16;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`HazardEras`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first creates a
+/// [`Guard`][super::Guard] or retires a record through the [`GlobalReclaim`]
+/// interface.
+/// The instance of the current thread can be accessed through
+/// [`HazardEras::with_local`][super::HazardEras::with_local].
+///
+/// [`GlobalReclaim`]: crate::GlobalReclaim
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    hazard_cache: RefCell<Vec<&'static Entry<HazardEra>>>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        Self {
+            global,
+            hazard_cache: RefCell::new(Vec::new()),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Acquires a hazard era, preferably from the thread local cache.
+    #[inline]
+    pub(crate) fn acquire_hazard(&self) -> &'static Entry<HazardEra> {
+        match self.hazard_cache.borrow_mut().pop() {
+            Some(hazard) => hazard,
+            None => self.global.acquire_hazard(),
+        }
+    }
+
+    /// Clears and returns the `hazard` to the thread local cache or releases
+    /// it globally, if the cache is full.
+    #[inline]
+    pub(crate) fn release_hazard(&self, hazard: &'static Entry<HazardEra>) {
+        hazard.clear();
+        let mut cache = self.hazard_cache.borrow_mut();
+        if cache.len() < HAZARD_CACHE_SIZE {
+            cache.push(hazard);
+        } else {
+            hazard.release();
+        }
+    }
+
+    /// Seals the given `record` with its `birth_era` and the current global
+    /// era and stores it until its lifetime no longer intersects with any
+    /// protected era.
+    #[inline]
+    pub(crate) fn retire_record(&self, record: Retired<HazardEras>, birth_era: usize) {
+        // the era must be loaded *after* the record has been unlinked
+        atomic::fence(Ordering::SeqCst);
+        let retire_era = self.global.current_era(Ordering::Relaxed);
+        self.retired.borrow_mut().push(Sealed::new(record, birth_era, retire_era));
+        // records allocated from now on must have a later birth era
+        self.global.advance_era(retire_era);
+
+        let count = self.retire_count.get().wrapping_add(1);
+        self.retire_count.set(count);
+        if count & (SCAN_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+
+    /// Scans all hazard eras and reclaims all unprotected records retired by
+    /// this thread or abandoned by exited threads.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        let protected = self.global.collect_protected();
+
+        // the records are taken out, because dropping them may retire further records
+        let unprotected = Sealed::split_unprotected(&mut self.retired.borrow_mut(), &protected);
+        for sealed in unprotected {
+            unsafe { sealed.reclaim() };
+        }
+
+        self.global.reclaim_abandoned(&protected);
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        for hazard in self.hazard_cache.get_mut().drain(..) {
+            hazard.release();
+        }
+
+        self.try_reclaim();
+
+        let retired = mem::take(self.retired.get_mut());
+        if !retired.is_empty() {
+            self.global.abandon(retired);
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! A hazard eras based memory reclamation scheme.
+//!
+//! This scheme combines the bounded memory usage of hazard pointers with the
+//! low overhead of epoch based schemes.
+//! All threads share a global *era* clock, which is advanced whenever a record
+//! is retired.
+//! Every record stores the era of its allocation in its [`Header`] and is
+//! sealed with the era of its retirement, so each record has a well defined
+//! lifetime interval.
+//! Every [`Guard`] owns a single *hazard era*, a globally visible slot for
+//! announcing the era in which the value it currently protects was loaded.
+//! Retired records are only reclaimed once their lifetime intervals do not
+//! contain any published era.
+//!
+//! Unlike hazard pointers, which have to be re-published (requiring a memory
+//! fence) for every loaded value, a hazard era only needs to be re-published
+//! when the global era has been advanced since its last publication.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::hazard_eras::{Guard, HazardEras, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::hazard_eras::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let mut guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed while `guard` protects it
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! HazardEras::try_reclaim();
+//! assert_eq!(*shared, 1);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+
+pub use self::local::Local;
+
+use core::mem;
+use core::ptr::NonNull;
+use core::sync::atomic::{self, Ordering};
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::registry::Entry;
+use crate::retired::Retired;
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, Reclaim, Record};
+
+use self::global::{Global, HazardEra, Sealed};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`HazardEras`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, HazardEras, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`HazardEras`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, HazardEras, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`HazardEras`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, HazardEras, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`HazardEras`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, HazardEras, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`HazardEras`]
+/// reclamation scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, HazardEras, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`HazardEras`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, HazardEras, N>;
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// HazardEras
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A hazard eras based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct HazardEras;
+
+/********** impl inherent *************************************************************************/
+
+impl HazardEras {
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Converts an `unlinked` record into a type-erased [`Retired`] record and
+    /// returns it alongside its birth era.
+    #[inline]
+    unsafe fn into_retired<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> (Retired<Self>, usize) {
+        let elem = MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null();
+        let birth_era = Record::<T, Self>::header_from_raw(elem.as_ptr()).birth_era();
+
+        (Retired::new_unchecked(elem), birth_era)
+    }
+
+    /// Retires the `record` in the local state of the current thread or, if
+    /// the thread local storage has already been destroyed, directly abandons
+    /// it to the global state.
+    #[inline]
+    fn retire_record((record, birth_era): (Retired<Self>, usize)) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => unsafe { (*local).retire_record(record, birth_era) },
+            Err(_) => {
+                atomic::fence(Ordering::SeqCst);
+                let retire_era = GLOBAL.current_era(Ordering::Relaxed);
+                GLOBAL.abandon(Some(Sealed::new(record, birth_era, retire_era)));
+                GLOBAL.advance_era(retire_era);
+            }
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for HazardEras {
+    type Guard = Guard;
+
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for HazardEras {
+    type Local = Local;
+    type RecordHeader = Header;
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        let (record, birth_era) = Self::into_retired(unlinked);
+        local.retire_record(record, birth_era);
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        let (record, birth_era) = Self::into_retired(unlinked);
+        local.retire_record(record, birth_era);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Header
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`RecordHeader`][Reclaim::RecordHeader] type for the [`HazardEras`]
+/// reclamation scheme, which stores the era in which a record was allocated.
+#[derive(Debug)]
+pub struct Header {
+    birth_era: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Header {
+    /// Returns the global era at the time the record was allocated.
+    #[inline]
+    pub fn birth_era(&self) -> usize {
+        self.birth_era
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Header {
+    /// Creates a new header with the current global era as birth era.
+    #[inline]
+    fn default() -> Self {
+        Self { birth_era: GLOBAL.current_era(Ordering::Acquire) }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`HazardEras`] reclamation
+/// scheme.
+///
+/// Each guard owns a hazard era for as long as it exists and can protect a
+/// single value at a time.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+    hazard: &'static Entry<HazardEra>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard owning a hazard era, which does not yet protect
+    /// any value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| Self { local: NonNull::from(local), hazard: local.acquire_hazard() })
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    /// Creates a new guard with its own hazard era, which protects the same
+    /// value as `self`.
+    #[inline]
+    fn clone(&self) -> Self {
+        let local = unsafe { self.local.as_ref() };
+        let hazard = local.acquire_hazard();
+        // the era is still protected by `self`, so no validation is required
+        if let Some(protected) = self.hazard.protected(Ordering::Relaxed) {
+            hazard.protect(protected);
+        }
+
+        Self { local: self.local, hazard }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().release_hazard(self.hazard) };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = HazardEras;
+
+    #[inline]
+    fn release(&mut self) {
+        self.hazard.clear();
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        let mut published = self.hazard.protected(Ordering::Relaxed);
+        loop {
+            let marked = atomic.load_raw(order);
+            if marked.is_null() {
+                self.hazard.clear();
+                return Marked::Null(marked.decompose_tag());
+            }
+
+            // the value is protected if it was loaded while the published era was current
+            let era = GLOBAL.current_era(Ordering::Acquire);
+            if published == Some(era) {
+                return unsafe { Marked::from_marked_ptr(marked) };
+            }
+
+            self.hazard.protect(era);
+            published = Some(era);
+        }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        let local = unsafe { self.local.as_ref() };
+        // a new era is published in a second hazard era, so that the previously protected value
+        // remains protected if the validation fails
+        let mut spare = None;
+        let mut published = self.hazard.protected(Ordering::Relaxed);
+        loop {
+            let marked = atomic.load_raw(order);
+            if marked != expected {
+                if let Some(hazard) = spare {
+                    local.release_hazard(hazard);
+                }
+
+                return Err(NotEqualError);
+            }
+
+            if marked.is_null() {
+                if let Some(hazard) = spare {
+                    local.release_hazard(hazard);
+                }
+
+                self.hazard.clear();
+                return Ok(Marked::Null(marked.decompose_tag()));
+            }
+
+            let era = GLOBAL.current_era(Ordering::Acquire);
+            if published == Some(era) {
+                if let Some(hazard) = spare {
+                    local.release_hazard(mem::replace(&mut self.hazard, hazard));
+                }
+
+                return Ok(unsafe { Marked::from_marked_ptr(marked) });
+            }
+
+            let hazard = *spare.get_or_insert_with(|| local.acquire_hazard());
+            hazard.protect(era);
+            published = Some(era);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+    use crate::Record;
+
+    use super::{Guard, HazardEras, GLOBAL};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    #[test]
+    fn birth_era() {
+        let era = GLOBAL.current_era(Ordering::Relaxed);
+        let owned = Owned::new(1);
+        let header = unsafe {
+            Record::<_, HazardEras>::header_from_raw_non_null(owned.inner.decompose_non_null())
+        };
+        assert!(header.birth_era() >= era);
+    }
+
+    #[test]
+    fn protected_not_reclaimed() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        HazardEras::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        guard.release();
+        HazardEras::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn protect_if_equal() {
+        let atomic = Atomic::new(1);
+        let mut guard = Guard::new();
+
+        let expected = atomic.load_raw(Ordering::Relaxed);
+        let shared = atomic.load_if_equal(expected, Ordering::Acquire, &mut guard);
+        assert_eq!(*shared.unwrap().unwrap(), 1);
+
+        let res = atomic.load_if_equal(Default::default(), Ordering::Acquire, &mut guard);
+        assert!(res.is_err());
+
+        let mut atomic = atomic;
+        drop(atomic.take());
+    }
+
+    #[test]
+    fn protect_if_equal_keeps_protection() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+        let mut other = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let _ = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        // a failed protection attempt must not release the previously protected value
+        let res = other.load_if_equal(Default::default(), Ordering::Acquire, &mut guard);
+        assert!(res.is_err());
+        HazardEras::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        guard.release();
+        HazardEras::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+
+        drop(other.take());
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn reclaim_abandoned() {
+        let count = test_util::retire_from_threads::<HazardEras>(4, 100);
+        test_util::reclaim_until(&count, 400, HazardEras::try_reclaim);
+    }
+}
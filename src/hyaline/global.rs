@@ -0,0 +1,260 @@
+//! The global state of the Hyaline reclamation scheme, which is shared by all
+//! threads.
+
+use core::ptr::{self, NonNull};
+
+use std::sync::atomic::{self, Ordering};
+
+use typenum::U1;
+
+use crate::pointer::{AtomicMarkedPtr, MarkedPtr};
+use crate::registry::{Entry, Registry};
+
+use super::Header;
+
+/// The tag bit marking a slot as currently occupied.
+const ACTIVE: usize = 1;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The registry of all slots, which threads enter while they hold any guards.
+///
+/// Slots are not bound to threads, so threads do not have to register
+/// themselves and slots are re-used once they have been left.
+#[derive(Debug)]
+pub(crate) struct Global {
+    slots: Registry<Slot>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state without any slots.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { slots: Registry::new() }
+    }
+
+    /// Acquires and enters an unoccupied slot.
+    #[inline]
+    pub fn enter(&self) -> &Entry<Slot> {
+        let slot = self.slots.acquire();
+        slot.enter();
+        slot
+    }
+
+    /// Leaves and releases the given `slot`, which reclaims all batches that
+    /// are no longer referenced by any other slot.
+    #[inline]
+    pub fn leave(&self, slot: &Entry<Slot>) {
+        slot.leave();
+        slot.release();
+    }
+
+    /// Inserts the given `batch` of retired records into all currently
+    /// occupied slots.
+    ///
+    /// If no slot is occupied, the batch is reclaimed immediately.
+    /// Otherwise, it is reclaimed by the thread leaving the last slot it has
+    /// been inserted in.
+    #[inline]
+    pub fn retire_batch(&self, batch: Batch) {
+        let refs = match batch.head {
+            Some(refs) => refs,
+            None => return,
+        };
+
+        // the first header holds the reference count, the remaining ones are inserted into slots
+        let mut curr = refs.as_ptr();
+        while !curr.is_null() {
+            unsafe {
+                *(*curr).batch.get() = refs.as_ptr();
+                curr = *(*curr).batch_next.get();
+            }
+        }
+
+        // all records must have been unlinked before checking the slots
+        atomic::fence(Ordering::SeqCst);
+
+        let mut inserted = 0;
+        let mut node = unsafe { *refs.as_ref().batch_next.get() };
+        for slot in self.slots.iter() {
+            if !slot.is_active() {
+                continue;
+            }
+
+            // there may be more occupied slots than records in the batch
+            if node.is_null() {
+                node = unsafe { Batch::pad(refs) };
+            }
+
+            if unsafe { slot.try_insert(node) } {
+                inserted += 1;
+                node = unsafe { *(*node).batch_next.get() };
+            }
+        }
+
+        let refs = unsafe { refs.as_ref() };
+        if refs.refs.fetch_add(inserted, Ordering::AcqRel) == -inserted {
+            unsafe { Batch::reclaim(refs) };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Slot
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A slot, which is occupied by a thread while it holds any guards.
+///
+/// While a slot is occupied, every retired batch inserts one of its record
+/// headers into the slot's list.
+#[derive(Debug)]
+pub(crate) struct Slot {
+    head: AtomicMarkedPtr<Header, U1>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Slot {
+    /// Marks the slot as occupied with an empty list.
+    #[inline]
+    fn enter(&self) {
+        self.head.store(MarkedPtr::compose(ptr::null_mut(), ACTIVE), Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Marks the slot as unoccupied and dereferences all batches that have
+    /// been inserted into it since it has been entered, reclaiming those that
+    /// are no longer referenced by any other slot.
+    #[inline]
+    fn leave(&self) {
+        let mut curr = self.head.swap(MarkedPtr::null(), Ordering::AcqRel).decompose_ptr();
+        while !curr.is_null() {
+            unsafe {
+                // the next header must be loaded before the batch may be reclaimed
+                let next = (*curr).next.load(Ordering::Relaxed);
+                let refs = &**(*curr).batch.get();
+                if refs.refs.fetch_sub(1, Ordering::AcqRel) == 1 {
+                    Batch::reclaim(refs);
+                }
+
+                curr = next;
+            }
+        }
+    }
+
+    /// Returns `true` if the slot is currently occupied.
+    #[inline]
+    fn is_active(&self) -> bool {
+        self.head.load(Ordering::Relaxed).decompose_tag() == ACTIVE
+    }
+
+    /// Inserts the `node` header into the slot's list, if it is occupied.
+    ///
+    /// # Safety
+    ///
+    /// The `node` must be a valid header of a retired batch.
+    #[inline]
+    unsafe fn try_insert(&self, node: *mut Header) -> bool {
+        let mut head = self.head.load(Ordering::Relaxed);
+        while head.decompose_tag() == ACTIVE {
+            (*node).next.store(head.decompose_ptr(), Ordering::Relaxed);
+            let new = MarkedPtr::compose(node, ACTIVE);
+            match self.head.compare_exchange_weak(head, new, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => head = actual,
+            }
+        }
+
+        false
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Slot {
+    #[inline]
+    fn default() -> Self {
+        Self { head: AtomicMarkedPtr::null() }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Batch
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A batch of retired records, which are linked through their headers.
+#[derive(Debug, Default)]
+pub(crate) struct Batch {
+    head: Option<NonNull<Header>>,
+    len: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Batch {
+    /// Returns the number of records in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the batch contains no records.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds the record with the given `header` to the batch.
+    ///
+    /// # Safety
+    ///
+    /// The `header` must belong to a record that has been retired.
+    #[inline]
+    pub unsafe fn push(&mut self, header: NonNull<Header>) {
+        *header.as_ref().batch_next.get() = self.head.map_or(ptr::null_mut(), NonNull::as_ptr);
+        self.head = Some(header);
+        self.len += 1;
+    }
+
+    /// Allocates an empty record and adds it to the batch that is referenced
+    /// by `refs`, returning its header.
+    ///
+    /// # Safety
+    ///
+    /// The `refs` header must be the first header of a batch that is not yet
+    /// fully retired.
+    #[inline]
+    unsafe fn pad(refs: NonNull<Header>) -> *mut Header {
+        let pad = super::padding();
+        let header = pad.as_ref();
+        *header.batch.get() = refs.as_ptr();
+        *header.batch_next.get() = *refs.as_ref().batch_next.get();
+        *refs.as_ref().batch_next.get() = pad.as_ptr();
+        pad.as_ptr()
+    }
+
+    /// Reclaims all records in the batch referenced by `refs`.
+    ///
+    /// # Safety
+    ///
+    /// The batch must no longer be referenced by any slot.
+    #[inline]
+    unsafe fn reclaim(refs: &Header) {
+        let mut curr = refs as *const Header as *mut Header;
+        while !curr.is_null() {
+            let next = *(*curr).batch_next.get();
+            let free = (*(*curr).free.get()).expect("retired record without deleter");
+            free(curr);
+            curr = next;
+        }
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Batch {}
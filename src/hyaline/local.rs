@@ -0,0 +1,133 @@
+//! The thread local state of the Hyaline reclamation scheme.
+
+use core::ptr::NonNull;
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+
+use crate::registry::Entry;
+
+use super::global::{Batch, Global, Slot};
+use super::Header;
+
+/// The number of records a thread collects before it retires them as a batch.
+const BATCH_SIZE: usize = 64;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`Hyaline`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first creates a
+/// [`Guard`][super::Guard] or retires a record through the [`GlobalReclaim`]
+/// interface.
+/// Threads are not registered in any global state, so a thread exiting
+/// merely retires its last (possibly incomplete) batch.
+/// The instance of the current thread can be accessed through
+/// [`Hyaline::with_local`][super::Hyaline::with_local].
+///
+/// [`Hyaline`]: super::Hyaline
+/// [`GlobalReclaim`]: crate::GlobalReclaim
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    guard_count: Cell<usize>,
+    slot: Cell<Option<&'static Entry<Slot>>>,
+    batch: RefCell<Batch>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        Self {
+            global,
+            guard_count: Cell::new(0),
+            slot: Cell::new(None),
+            batch: RefCell::new(Batch::default()),
+        }
+    }
+
+    /// Returns `true` if the thread currently occupies a slot for at least one
+    /// guard.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.guard_count.get() > 0
+    }
+
+    /// Increments the guard count and enters a slot, if the thread does not
+    /// already occupy one.
+    #[inline]
+    pub(crate) fn acquire_guard(&self) {
+        let count = self.guard_count.get();
+        self.guard_count.set(count.checked_add(1).expect("guard count overflow"));
+
+        if count == 0 {
+            self.slot.set(Some(self.global.enter()));
+        }
+    }
+
+    /// Decrements the guard count and leaves the occupied slot, if there are
+    /// no more guards left.
+    #[inline]
+    pub(crate) fn release_guard(&self) {
+        let count = self.guard_count.get() - 1;
+        self.guard_count.set(count);
+
+        if count == 0 {
+            // leaving may reclaim records, whose destructors may create further guards
+            let slot = self.slot.take().unwrap();
+            self.global.leave(slot);
+        }
+    }
+
+    /// Adds the record with the given `header` to the current batch and
+    /// retires the batch once it is full.
+    ///
+    /// # Safety
+    ///
+    /// The `header` must belong to a record that has been retired.
+    #[inline]
+    pub(crate) unsafe fn retire_record(&self, header: NonNull<Header>) {
+        let full = {
+            let mut batch = self.batch.borrow_mut();
+            batch.push(header);
+            match batch.len() {
+                len if len >= BATCH_SIZE => Some(mem::take(&mut *batch)),
+                _ => None,
+            }
+        };
+
+        // the batch is taken out, because reclaiming it may retire further records
+        if let Some(batch) = full {
+            self.global.retire_batch(batch);
+        }
+    }
+
+    /// Retires the current batch, even if it is not yet full.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        // the batch is taken out, because reclaiming it may retire further records
+        let batch = mem::take(&mut *self.batch.borrow_mut());
+        if !batch.is_empty() {
+            self.global.retire_batch(batch);
+        }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot.take() {
+            self.global.leave(slot);
+        }
+
+        self.try_reclaim();
+    }
+}
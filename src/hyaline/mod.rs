@@ -0,0 +1,399 @@
+//! A Hyaline based memory reclamation scheme, which reclaims batches of
+//! retired records through reference counting.
+//!
+//! Threads *enter* one of a global set of slots when they create their first
+//! [`Guard`] and *leave* it again once their last guard is dropped.
+//! Retired records are collected into thread local batches, which are linked
+//! through the records' [`Header`]s.
+//! Once a batch is full, one of its records is inserted into the list of
+//! every currently occupied slot and the batch's reference count is set to
+//! the number of slots it has been inserted in.
+//! Every thread leaving a slot dereferences all batches inserted into it
+//! during its occupancy and the last thread to do so reclaims the batch.
+//!
+//! Unlike in most other schemes, threads are neither registered nor do they
+//! scan the state of other threads for reclaiming records, which makes this
+//! scheme particularly suitable for workloads with many short-lived threads.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::hyaline::{Guard, Hyaline, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::hyaline::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed before `guard` is dropped
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! Hyaline::try_reclaim();
+//! assert_eq!(*shared, 1);
+//! drop(guard);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+
+pub use self::local::Local;
+
+use core::cell::UnsafeCell;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicIsize, AtomicPtr, Ordering};
+
+use typenum::Unsigned;
+
+use crate::allocator::{self, Allocation};
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, ProtectRegion, Reclaim, Record};
+
+use self::global::{Batch, Global};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Hyaline`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Hyaline, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Hyaline`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Hyaline, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Hyaline`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Hyaline, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Hyaline`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, Hyaline, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Hyaline`]
+/// reclamation scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Hyaline, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Hyaline`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Hyaline, N>;
+
+static GLOBAL: Global = Global::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Hyaline
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A Hyaline based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct Hyaline;
+
+/********** impl inherent *************************************************************************/
+
+impl Hyaline {
+    /// Returns `true` if the current thread occupies a slot for at least one
+    /// [`Guard`].
+    #[inline]
+    pub fn is_active() -> bool {
+        LOCAL.with(Local::is_active)
+    }
+
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Stores the type-specific deleter in the header of the `unlinked`
+    /// record and returns a pointer to the header.
+    #[inline]
+    unsafe fn seal<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> NonNull<Header> {
+        let elem = MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null();
+        let header = Record::<T, Self>::header_from_raw_non_null(elem);
+        *header.free.get() = Some(free::<T>);
+
+        NonNull::from(header)
+    }
+
+    /// Adds the record with the given `header` to the current batch of the
+    /// current thread or, if the thread local storage has already been
+    /// destroyed, directly retires it as a batch of its own.
+    #[inline]
+    unsafe fn retire_record(header: NonNull<Header>) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => (*local).retire_record(header),
+            Err(_) => {
+                let mut batch = Batch::default();
+                batch.push(header);
+                GLOBAL.retire_batch(batch);
+            }
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Hyaline {
+    type Guard = Guard;
+
+    /// Retires the current thread's batch of retired records, even if it is
+    /// not yet full.
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::seal(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::seal(unlinked));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Hyaline {
+    type Local = Local;
+    type RecordHeader = Header;
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::seal(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::seal(unlinked));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Header
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`RecordHeader`][Reclaim::RecordHeader] type for the [`Hyaline`]
+/// reclamation scheme.
+///
+/// Once a record is retired, its header links it to the other records in its
+/// batch and to the lists of the slots it is inserted in.
+/// The header of the first record in a batch also holds the batch's reference
+/// count.
+#[derive(Debug)]
+pub struct Header {
+    refs: AtomicIsize,
+    next: AtomicPtr<Header>,
+    batch: UnsafeCell<*mut Header>,
+    batch_next: UnsafeCell<*mut Header>,
+    free: UnsafeCell<Option<unsafe fn(*mut Header)>>,
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Header {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            refs: AtomicIsize::new(0),
+            next: AtomicPtr::new(ptr::null_mut()),
+            batch: UnsafeCell::new(ptr::null_mut()),
+            batch_next: UnsafeCell::new(ptr::null_mut()),
+            free: UnsafeCell::new(None),
+        }
+    }
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+// the non-atomic fields are only written by the retiring thread before the
+// batch is inserted into any slot and only read afterwards
+unsafe impl Send for Header {}
+unsafe impl Sync for Header {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Hyaline`] reclamation
+/// scheme.
+///
+/// A guard keeps the thread it is created in inside its slot for as long as
+/// it exists and protects all values loaded during this time.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard and enters a slot, if the current thread does not
+    /// already occupy one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| {
+            local.acquire_guard();
+            Self { local: NonNull::from(local) }
+        })
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    #[inline]
+    fn clone(&self) -> Self {
+        unsafe { self.local.as_ref().acquire_guard() };
+        Self { local: self.local }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().release_guard() };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Hyaline;
+
+    /// This is a no-op, since the guard protects all values as long as it
+    /// exists.
+    #[inline]
+    fn release(&mut self) {}
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(atomic.load_raw(order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        match atomic.load_raw(order) {
+            raw if raw == expected => Ok(unsafe { Marked::from_marked_ptr(raw) }),
+            _ => Err(NotEqualError),
+        }
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl ProtectRegion for Guard {}
+
+/********** helper functions **********************************************************************/
+
+/// Allocates an empty record for padding a batch with fewer records than
+/// occupied slots and returns its header.
+#[inline]
+fn padding() -> NonNull<Header> {
+    let record = Allocation::alloc(Record::<(), Hyaline>::new(()), allocator::Global);
+    let header = NonNull::from(unsafe { record.as_ref() }.header());
+    unsafe { *header.as_ref().free.get() = Some(free::<()>) };
+    header
+}
+
+/// Reclaims the record of type `T` containing the given `header`.
+#[inline]
+unsafe fn free<T>(header: *mut Header) {
+    let record = (header as usize) - Record::<T, Hyaline>::offset_header();
+    Allocation::reclaim(NonNull::new_unchecked(record as *mut Record<T, Hyaline>));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, Hyaline};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    fn reclaim_until(count: &AtomicUsize, expected: usize) {
+        test_util::reclaim_until(count, expected, Hyaline::try_reclaim);
+    }
+
+    #[test]
+    fn enter_nested() {
+        assert!(!Hyaline::is_active());
+        let guard = Guard::new();
+        let clone = guard.clone();
+        drop(guard);
+        assert!(Hyaline::is_active());
+        drop(clone);
+        assert!(!Hyaline::is_active());
+    }
+
+    #[test]
+    fn protected_not_reclaimed() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        Hyaline::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        drop(guard);
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn short_lived_threads() {
+        let count = test_util::retire_from_threads::<Hyaline>(16, 25);
+        reclaim_until(&count, 400);
+    }
+}
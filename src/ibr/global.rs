@@ -0,0 +1,251 @@
+//! The global state of the interval-based reclamation scheme, which is shared
+//! by all threads.
+
+use core::ptr::NonNull;
+
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::registry::{Entry, Registry};
+use crate::retired::Retired;
+
+use super::{Header, Ibr};
+
+/// The epoch value of a reservation that does not protect any records.
+pub(crate) const NONE: usize = 0;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global epoch clock, the registry of all participating threads and the
+/// records that were left behind by exiting threads.
+#[derive(Debug)]
+pub(crate) struct Global {
+    epoch: AtomicUsize,
+    participants: Registry<Participant>,
+    abandoned: Mutex<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            epoch: AtomicUsize::new(NONE + 1),
+            participants: Registry::new(),
+            abandoned: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Loads the current global epoch.
+    #[inline]
+    pub fn current_epoch(&self, order: Ordering) -> usize {
+        self.epoch.load(order)
+    }
+
+    /// Advances the global epoch, unless it has already been advanced since
+    /// `epoch` was loaded.
+    #[inline]
+    pub fn advance_epoch(&self, epoch: usize) {
+        let _ = self.epoch.compare_exchange(epoch, epoch + 1, Ordering::AcqRel, Ordering::Relaxed);
+    }
+
+    /// Registers a new participant, which initially reserves no epochs.
+    #[inline]
+    pub fn register(&self) -> &Entry<Participant> {
+        self.participants.acquire()
+    }
+
+    /// Collects the epoch intervals currently reserved by all participants.
+    #[inline]
+    pub fn collect_reservations(&self) -> Vec<(usize, usize)> {
+        // synchronizes with the fences in `Participant::reserve` and `Participant::extend`
+        atomic::fence(Ordering::SeqCst);
+        self.participants
+            .iter()
+            .filter_map(|participant| participant.reservation(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// Hands over all records in `records` to the global state, from where
+    /// they can eventually be reclaimed by any other thread.
+    #[inline]
+    pub fn abandon(&self, records: impl IntoIterator<Item = Sealed>) {
+        let mut abandoned = self.abandoned.lock().unwrap_or_else(|err| err.into_inner());
+        abandoned.extend(records);
+    }
+
+    /// Reclaims all abandoned records whose lifetimes do not intersect with
+    /// any of the `reservations`.
+    ///
+    /// If another thread is currently accessing the abandoned records, this is
+    /// a no-op.
+    #[inline]
+    pub fn reclaim_abandoned(&self, reservations: &[(usize, usize)]) {
+        let unprotected = match self.abandoned.try_lock() {
+            Ok(mut abandoned) => Sealed::split_unprotected(&mut abandoned, reservations),
+            Err(_) => return,
+        };
+
+        for sealed in unprotected {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Global {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline]
+    fn drop(&mut self) {
+        let abandoned = self.abandoned.get_mut().unwrap_or_else(|err| err.into_inner());
+        for sealed in abandoned.drain(..) {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Participant
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The globally visible state of a participating thread, which is the
+/// interval of epochs it has reserved.
+#[derive(Debug, Default)]
+pub(crate) struct Participant {
+    lower: AtomicUsize,
+    upper: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Participant {
+    /// Reserves the interval consisting only of the current global epoch.
+    #[inline]
+    pub fn reserve(&self, global: &Global) {
+        let mut epoch = global.current_epoch(Ordering::Relaxed);
+        loop {
+            self.lower.store(epoch, Ordering::Relaxed);
+            self.upper.store(epoch, Ordering::Relaxed);
+            atomic::fence(Ordering::SeqCst);
+
+            // the global epoch may have been advanced before the reservation became visible
+            match global.current_epoch(Ordering::Relaxed) {
+                current if current == epoch => return,
+                current => epoch = current,
+            }
+        }
+    }
+
+    /// Extends the upper end of the reserved interval to `epoch`.
+    ///
+    /// The extension is only established once the caller has validated that
+    /// the global epoch has not been advanced in the meantime.
+    #[inline]
+    pub fn extend(&self, epoch: usize) {
+        self.upper.store(epoch, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Clears the reserved interval.
+    #[inline]
+    pub fn clear(&self) {
+        self.lower.store(NONE, Ordering::Release);
+        self.upper.store(NONE, Ordering::Release);
+    }
+
+    /// Returns the upper end of the reserved interval, if there is any.
+    #[inline]
+    pub fn upper(&self, order: Ordering) -> Option<usize> {
+        match self.upper.load(order) {
+            NONE => None,
+            upper => Some(upper),
+        }
+    }
+
+    /// Returns the reserved interval, if there is any.
+    #[inline]
+    pub fn reservation(&self, order: Ordering) -> Option<(usize, usize)> {
+        match (self.lower.load(order), self.upper.load(order)) {
+            (NONE, _) | (_, NONE) => None,
+            reservation => Some(reservation),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Sealed
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record alongside a pointer to its header, which contains its
+/// birth and retire epochs.
+#[derive(Debug)]
+pub(crate) struct Sealed {
+    header: NonNull<Header>,
+    record: Retired<Ibr>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Sealed {
+    /// Creates a new sealed record.
+    ///
+    /// # Safety
+    ///
+    /// The `header` must point to the header of `record` and its retire epoch
+    /// must have been set.
+    #[inline]
+    pub unsafe fn new(record: Retired<Ibr>, header: NonNull<Header>) -> Self {
+        Self { header, record }
+    }
+
+    /// Returns `true` if the lifetime of the record intersects with any of the
+    /// `reservations`.
+    #[inline]
+    pub fn is_protected(&self, reservations: &[(usize, usize)]) -> bool {
+        let header = unsafe { self.header.as_ref() };
+        let (birth, retire) = (header.birth_epoch(), header.retire_epoch().unwrap());
+        reservations.iter().any(|&(lower, upper)| birth <= upper && retire >= lower)
+    }
+
+    /// Reclaims the sealed record.
+    ///
+    /// # Safety
+    ///
+    /// The record must not be protected and must not be reclaimed more than
+    /// once.
+    #[inline]
+    pub unsafe fn reclaim(mut self) {
+        self.record.reclaim();
+    }
+
+    /// Removes all records whose lifetimes do not intersect with any of the
+    /// `reservations` from `records` and returns them.
+    #[inline]
+    pub fn split_unprotected(
+        records: &mut Vec<Sealed>,
+        reservations: &[(usize, usize)],
+    ) -> Vec<Sealed> {
+        let (unprotected, remaining) =
+            records.drain(..).partition(|sealed| !sealed.is_protected(reservations));
+        *records = remaining;
+        unprotected
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Sealed {}
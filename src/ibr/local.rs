@@ -0,0 +1,136 @@
+//! The thread local state of the interval-based reclamation scheme.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+
+use crate::registry::Entry;
+
+use super::global::{Global, Participant, Sealed};
+
+/// The number of records a thread retires before it scans all reservations
+/// and reclaims its unprotected records (must be a power of two).
+const SCAN_THRESHOLD: usize = 128;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`Ibr`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first creates a
+/// [`Guard`][super::Guard] or retires a record through the [`GlobalReclaim`]
+/// interface.
+/// The instance of the current thread can be accessed through
+/// [`Ibr::with_local`][super::Ibr::with_local].
+///
+/// [`GlobalReclaim`]: crate::GlobalReclaim
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    participant: &'static Entry<Participant>,
+    guard_count: Cell<usize>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state and registers it as participant in the
+    /// global state.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        Self {
+            global,
+            participant: global.register(),
+            guard_count: Cell::new(0),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns `true` if the thread currently reserves an interval of epochs
+    /// for at least one guard.
+    #[inline]
+    pub fn is_reserved(&self) -> bool {
+        self.guard_count.get() > 0
+    }
+
+    /// Increments the guard count and reserves the current global epoch, if
+    /// no interval is reserved yet.
+    #[inline]
+    pub(crate) fn acquire_guard(&self) {
+        let count = self.guard_count.get();
+        self.guard_count.set(count.checked_add(1).expect("guard count overflow"));
+
+        if count == 0 {
+            self.participant.reserve(self.global);
+        }
+    }
+
+    /// Decrements the guard count and clears the reserved interval, if there
+    /// are no more guards left.
+    #[inline]
+    pub(crate) fn release_guard(&self) {
+        let count = self.guard_count.get() - 1;
+        self.guard_count.set(count);
+
+        if count == 0 {
+            self.participant.clear();
+        }
+    }
+
+    /// Returns the participant representing the thread in the global state.
+    #[inline]
+    pub(crate) fn participant(&self) -> &Participant {
+        self.participant
+    }
+
+    /// Stores the given `sealed` record until its lifetime no longer
+    /// intersects with any reserved interval.
+    #[inline]
+    pub(crate) fn retire_record(&self, sealed: Sealed) {
+        self.retired.borrow_mut().push(sealed);
+
+        let count = self.retire_count.get().wrapping_add(1);
+        self.retire_count.set(count);
+        if count & (SCAN_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+
+    /// Scans all reserved intervals and reclaims all unprotected records
+    /// retired by this thread or abandoned by exited threads.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        let reservations = self.global.collect_reservations();
+
+        // the records are taken out, because dropping them may retire further records
+        let unprotected = Sealed::split_unprotected(&mut self.retired.borrow_mut(), &reservations);
+        for sealed in unprotected {
+            unsafe { sealed.reclaim() };
+        }
+
+        self.global.reclaim_abandoned(&reservations);
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        if self.is_reserved() {
+            self.participant.clear();
+        }
+
+        self.try_reclaim();
+        self.participant.release();
+
+        let retired = mem::take(self.retired.get_mut());
+        if !retired.is_empty() {
+            self.global.abandon(retired);
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! An interval-based memory reclamation scheme (2GE-IBR).
+//!
+//! All threads share a global epoch clock, which is advanced whenever a
+//! record is retired.
+//! Every record stores the epochs of its allocation and its retirement in its
+//! [`Header`], so each record has a well defined lifetime interval.
+//! Creating a [`Guard`] *reserves* an interval of epochs for the current
+//! thread, which initially consists only of the current global epoch.
+//! Whenever a value is loaded through a guard, the upper end of the interval
+//! is extended to the current global epoch, if it has been advanced in the
+//! meantime.
+//! Retired records are only reclaimed once their lifetime intervals do not
+//! intersect with any reserved interval.
+//!
+//! Like in epoch based schemes, all values loaded by a thread are protected
+//! as long as any guard exists.
+//! Unlike in epoch based schemes, however, a stalled thread can only prevent
+//! the reclamation of records that were allocated before it stalled, which
+//! bounds the amount of memory that can not be reclaimed.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::ibr::{Guard, Ibr, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::ibr::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed before `guard` is dropped
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! Ibr::try_reclaim();
+//! assert_eq!(*shared, 1);
+//! drop(guard);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+
+pub use self::local::Local;
+
+use core::ptr::NonNull;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::retired::Retired;
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, ProtectRegion, Reclaim, Record};
+
+use self::global::{Global, Sealed, NONE};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Ibr`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Ibr, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Ibr`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Ibr, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Ibr`] reclamation scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Ibr, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Ibr`] reclamation scheme.
+pub type Owned<T, N> = crate::Owned<T, Ibr, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Ibr`] reclamation
+/// scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Ibr, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Ibr`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Ibr, N>;
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Ibr
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An interval-based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct Ibr;
+
+/********** impl inherent *************************************************************************/
+
+impl Ibr {
+    /// Returns `true` if the current thread reserves an interval of epochs
+    /// for at least one [`Guard`].
+    #[inline]
+    pub fn is_reserved() -> bool {
+        LOCAL.with(Local::is_reserved)
+    }
+
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Stores the current global epoch as retire epoch in the header of the
+    /// `unlinked` record, advances the global epoch and converts the record
+    /// into a type-erased [`Sealed`] record.
+    #[inline]
+    unsafe fn seal<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> Sealed {
+        let elem = MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null();
+        let header = Record::<T, Self>::header_from_raw_non_null(elem);
+
+        // the epoch must be loaded *after* the record has been unlinked
+        atomic::fence(Ordering::SeqCst);
+        let epoch = GLOBAL.current_epoch(Ordering::Relaxed);
+        header.retire_epoch.store(epoch, Ordering::Relaxed);
+        // records allocated from now on must have a later birth epoch
+        GLOBAL.advance_epoch(epoch);
+
+        Sealed::new(Retired::new_unchecked(elem), NonNull::from(header))
+    }
+
+    /// Retires the `sealed` record in the local state of the current thread
+    /// or, if the thread local storage has already been destroyed, directly
+    /// abandons it to the global state.
+    #[inline]
+    fn retire_record(sealed: Sealed) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => unsafe { (*local).retire_record(sealed) },
+            Err(_) => GLOBAL.abandon(Some(sealed)),
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Ibr {
+    type Guard = Guard;
+
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::seal(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::seal(unlinked));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Ibr {
+    type Local = Local;
+    type RecordHeader = Header;
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::seal(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::seal(unlinked));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Header
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`RecordHeader`][Reclaim::RecordHeader] type for the [`Ibr`]
+/// reclamation scheme, which stores the epochs in which a record was
+/// allocated and retired.
+#[derive(Debug)]
+pub struct Header {
+    birth_epoch: usize,
+    retire_epoch: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Header {
+    /// Returns the global epoch at the time the record was allocated.
+    #[inline]
+    pub fn birth_epoch(&self) -> usize {
+        self.birth_epoch
+    }
+
+    /// Returns the global epoch at the time the record was retired or `None`,
+    /// if it has not been retired yet.
+    #[inline]
+    pub fn retire_epoch(&self) -> Option<usize> {
+        match self.retire_epoch.load(Ordering::Relaxed) {
+            NONE => None,
+            epoch => Some(epoch),
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Header {
+    /// Creates a new header with the current global epoch as birth epoch.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            birth_epoch: GLOBAL.current_epoch(Ordering::Acquire),
+            retire_epoch: AtomicUsize::new(NONE),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Ibr`] reclamation
+/// scheme.
+///
+/// A guard keeps an interval of epochs reserved for the thread it is created
+/// in for as long as it exists and protects all values loaded during this
+/// time.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard and reserves the current global epoch for the
+    /// current thread, if it does not already reserve an interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| {
+            local.acquire_guard();
+            Self { local: NonNull::from(local) }
+        })
+    }
+
+    /// Loads a value from `atomic` and extends the upper end of the reserved
+    /// interval until it covers the epoch in which the value was loaded.
+    #[inline]
+    fn load_extend<T, N: Unsigned>(
+        &self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> MarkedPtr<T, N> {
+        let participant = unsafe { self.local.as_ref().participant() };
+        let mut upper = participant.upper(Ordering::Relaxed);
+        loop {
+            let marked = atomic.load_raw(order);
+            if marked.is_null() {
+                return marked;
+            }
+
+            // the value is protected if it was loaded while the upper epoch was current
+            let epoch = GLOBAL.current_epoch(Ordering::Acquire);
+            if upper == Some(epoch) {
+                return marked;
+            }
+
+            participant.extend(epoch);
+            upper = Some(epoch);
+        }
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    #[inline]
+    fn clone(&self) -> Self {
+        unsafe { self.local.as_ref().acquire_guard() };
+        Self { local: self.local }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().release_guard() };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Ibr;
+
+    /// This is a no-op, since the guard protects all values as long as it
+    /// exists.
+    #[inline]
+    fn release(&mut self) {}
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(self.load_extend(atomic, order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        match self.load_extend(atomic, order) {
+            raw if raw == expected => Ok(unsafe { Marked::from_marked_ptr(raw) }),
+            _ => Err(NotEqualError),
+        }
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl ProtectRegion for Guard {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, Ibr};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    fn reclaim_until(count: &AtomicUsize, expected: usize) {
+        test_util::reclaim_until(count, expected, Ibr::try_reclaim);
+    }
+
+    #[test]
+    fn reserve_nested() {
+        assert!(!Ibr::is_reserved());
+        let guard = Guard::new();
+        let clone = guard.clone();
+        drop(guard);
+        assert!(Ibr::is_reserved());
+        drop(clone);
+        assert!(!Ibr::is_reserved());
+    }
+
+    #[test]
+    fn protected_not_reclaimed() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        Ibr::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        drop(guard);
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn stalled_guard_bounded() {
+        let guard = Guard::new();
+
+        // the retirement advances the global epoch past the reserved interval
+        let old = Atomic::new(0);
+        unsafe { old.swap(Owned::none(), Ordering::AcqRel).unwrap().retire() };
+
+        // records allocated after the reservation can be reclaimed despite the guard
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+        unsafe { atomic.swap(Owned::none(), Ordering::AcqRel).unwrap().retire() };
+
+        reclaim_until(&count, 1);
+        drop(guard);
+    }
+
+    #[test]
+    fn reclaim_abandoned() {
+        let count = test_util::retire_from_threads::<Ibr>(4, 100);
+        reclaim_until(&count, 400);
+    }
+}
@@ -0,0 +1,476 @@
+//! A wrapper for any reclamation scheme that records statistics about
+//! retired and reclaimed records and live guards.
+//!
+//! [`Instrumented<R>`][Instrumented] forwards all operations to the wrapped
+//! scheme `R`, so it can be used as a drop-in replacement for `R` in any data
+//! structure that is generic over its reclamation scheme.
+//! The recorded statistics can be retrieved at any time by calling
+//! [`Instrumented::snapshot`].
+//!
+//! Statistics are recorded separately for every wrapped scheme type, but are
+//! shared by all data structures using the same instrumented scheme.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::instrumented::Instrumented;
+//! use reclaim::leak::Leaking;
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::Atomic<T, Instrumented<Leaking>, reclaim::typenum::U0>;
+//! type Owned<T> = reclaim::Owned<T, Instrumented<Leaking>, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//! let guard = Instrumented::<Leaking>::guard();
+//! assert_eq!(Instrumented::<Leaking>::snapshot().live_guards, 1);
+//!
+//! let unlinked = atomic.swap(Owned::new(2), Ordering::AcqRel).unwrap();
+//! unsafe { unlinked.retire() };
+//!
+//! let snapshot = Instrumented::<Leaking>::snapshot();
+//! assert_eq!(snapshot.retired_records, 1);
+//! assert_eq!(snapshot.outstanding_records(), 1);
+//! # drop(guard);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use core::any::TypeId;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use typenum::Unsigned;
+
+use crate::pointer::{Marked, MarkedPointer, MarkedPtr};
+use crate::{AcquireResult, Atomic, GlobalReclaim, Protect, ProtectRegion, Reclaim, Record};
+use crate::{Shared, Unlinked};
+
+static STATS: AtomicPtr<Stats> = AtomicPtr::new(ptr::null_mut());
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Instrumented
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A reclamation scheme wrapper, which forwards all operations to the
+/// reclamation scheme `R` and records statistics about them.
+pub struct Instrumented<R>(PhantomData<R>);
+
+/********** impl inherent *************************************************************************/
+
+impl<R: Reclaim> Instrumented<R> {
+    /// Returns a snapshot of the statistics recorded for the instrumented
+    /// reclamation scheme `R`.
+    ///
+    /// The individual counts are loaded independently, so the snapshot may
+    /// not be consistent if records are concurrently retired or reclaimed.
+    #[inline]
+    pub fn snapshot() -> Snapshot {
+        Stats::get::<R>().snapshot()
+    }
+
+    /// Records the retirement of `unlinked` and converts it into a record of
+    /// the wrapped reclamation scheme.
+    ///
+    /// The record's memory is still released as a record of the instrumented
+    /// scheme, which records its reclamation through the
+    /// [`on_reclaim`][Reclaim::on_reclaim] hook.
+    #[inline]
+    fn forward<T, N: Unsigned>(unlinked: Unlinked<T, Self, N>) -> Unlinked<T, R, N> {
+        debug_assert_eq!(
+            Record::<T, Self>::offset_elem(),
+            Record::<T, R>::offset_elem(),
+            "instrumented record layout differs from the wrapped scheme's"
+        );
+
+        let stats = Stats::get::<R>();
+        stats.retired_records.fetch_add(1, Ordering::Relaxed);
+        stats.retired_bytes.fetch_add(mem::size_of::<Record<T, R>>(), Ordering::Relaxed);
+
+        unsafe { Unlinked::from_marked_ptr(unlinked.into_marked_ptr().cast()) }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R> fmt::Debug for Instrumented<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Instrumented").finish()
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<R> Default for Instrumented<R> {
+    #[inline]
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl<R: GlobalReclaim> GlobalReclaim for Instrumented<R> {
+    type Guard = Guard<R::Guard>;
+
+    #[inline]
+    fn try_reclaim() {
+        R::try_reclaim();
+    }
+
+    #[inline]
+    fn try_reclaim_quiescent() {
+        R::try_reclaim_quiescent();
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, Self, N>) {
+        R::retire(Self::forward(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, Self, N>) {
+        R::retire_unchecked(Self::forward(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_batch<T: 'static, N: Unsigned>(
+        batch: impl IntoIterator<Item = Unlinked<T, Self, N>>,
+    ) {
+        R::retire_batch(batch.into_iter().map(Self::forward));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl<R: Reclaim> Reclaim for Instrumented<R> {
+    type Local = R::Local;
+    type RecordHeader = R::RecordHeader;
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(
+        local: &Self::Local,
+        unlinked: Unlinked<T, Self, N>,
+    ) {
+        R::retire_local(local, Self::forward(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(
+        local: &Self::Local,
+        unlinked: Unlinked<T, Self, N>,
+    ) {
+        R::retire_local_unchecked(local, Self::forward(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_batch<T: 'static, N: Unsigned>(
+        local: &Self::Local,
+        batch: impl IntoIterator<Item = Unlinked<T, Self, N>>,
+    ) {
+        R::retire_local_batch(local, batch.into_iter().map(Self::forward));
+    }
+
+    #[inline]
+    fn on_access<T>(elem: NonNull<T>) {
+        R::on_access(elem);
+    }
+
+    #[inline]
+    fn on_reclaim<T>(elem: NonNull<T>) {
+        let stats = Stats::get::<R>();
+        stats.reclaimed_records.fetch_add(1, Ordering::Relaxed);
+        stats.reclaimed_bytes.fetch_add(mem::size_of::<Record<T, R>>(), Ordering::Relaxed);
+        R::on_reclaim(elem);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Instrumented`]
+/// reclamation scheme wrapper, which wraps a guard of the instrumented
+/// scheme.
+#[derive(Debug)]
+pub struct Guard<G: Protect> {
+    inner: G,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<G: Protect> Guard<G> {
+    /// Wraps the given `inner` guard of the instrumented reclamation scheme.
+    #[inline]
+    pub fn new(inner: G) -> Self {
+        let stats = Stats::get::<G::Reclaimer>();
+        stats.live_guards.fetch_add(1, Ordering::Relaxed);
+        Self { inner }
+    }
+
+    /// Casts a reference to an `atomic` of the instrumented reclamation scheme
+    /// to one of the wrapped scheme.
+    #[inline]
+    fn cast_atomic<T, N: Unsigned>(
+        atomic: &Atomic<T, Instrumented<G::Reclaimer>, N>,
+    ) -> &Atomic<T, G::Reclaimer, N> {
+        // both types are transparent wrappers around the same atomic pointer
+        unsafe { &*(atomic as *const Atomic<_, _, N> as *const Atomic<T, G::Reclaimer, N>) }
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<G: Protect> Clone for Guard<G> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<G: Protect + Default> Default for Guard<G> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(G::default())
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<G: Protect> Drop for Guard<G> {
+    #[inline]
+    fn drop(&mut self) {
+        let stats = Stats::get::<G::Reclaimer>();
+        stats.live_guards.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl<G: Protect> Protect for Guard<G> {
+    type Reclaimer = Instrumented<G::Reclaimer>;
+
+    #[inline]
+    fn release(&mut self) {
+        self.inner.release();
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaimer, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, Self::Reclaimer, N>> {
+        let marked = self.inner.protect(Self::cast_atomic(atomic), order);
+        unsafe { Marked::from_marked_ptr(marked.into_marked_ptr()) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaimer, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        self.inner
+            .protect_if_equal(Self::cast_atomic(atomic), expected, order)
+            .map(|marked| unsafe { Marked::from_marked_ptr(marked.into_marked_ptr()) })
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl<G: ProtectRegion> ProtectRegion for Guard<G> {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Snapshot
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A snapshot of the statistics recorded for an [`Instrumented`] reclamation
+/// scheme.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Snapshot {
+    /// The total number of retired records.
+    pub retired_records: usize,
+    /// The total size of all retired records in bytes, including their
+    /// headers.
+    pub retired_bytes: usize,
+    /// The total number of reclaimed records.
+    pub reclaimed_records: usize,
+    /// The total size of all reclaimed records in bytes, including their
+    /// headers.
+    pub reclaimed_bytes: usize,
+    /// The number of currently existing guards.
+    pub live_guards: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Snapshot {
+    /// Returns the number of records that have been retired but not yet
+    /// reclaimed.
+    #[inline]
+    pub fn outstanding_records(&self) -> usize {
+        self.retired_records.saturating_sub(self.reclaimed_records)
+    }
+
+    /// Returns the size of all records in bytes that have been retired but
+    /// not yet reclaimed.
+    #[inline]
+    pub fn outstanding_bytes(&self) -> usize {
+        self.retired_bytes.saturating_sub(self.reclaimed_bytes)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Stats
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The statistics for a single instrumented reclamation scheme, which are
+/// stored in a global append-only list.
+#[derive(Debug)]
+struct Stats {
+    scheme: TypeId,
+    retired_records: AtomicUsize,
+    retired_bytes: AtomicUsize,
+    reclaimed_records: AtomicUsize,
+    reclaimed_bytes: AtomicUsize,
+    live_guards: AtomicUsize,
+    next: *const Stats,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Stats {
+    /// Returns the statistics for the reclamation scheme `R`, which are
+    /// created on first use.
+    #[inline]
+    fn get<R: 'static>() -> &'static Self {
+        let scheme = TypeId::of::<R>();
+        let mut new: Option<&'static mut Self> = None;
+
+        let mut head = STATS.load(Ordering::Acquire);
+        loop {
+            let mut curr = head as *const Self;
+            while let Some(stats) = unsafe { curr.as_ref() } {
+                if stats.scheme == scheme {
+                    if let Some(new) = new {
+                        mem::drop(unsafe { Box::from_raw(new) });
+                    }
+
+                    return stats;
+                }
+
+                curr = stats.next;
+            }
+
+            let stats = new.get_or_insert_with(|| Box::leak(Box::new(Self::new(scheme))));
+            stats.next = head;
+            match STATS.compare_exchange(head, *stats, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return new.unwrap(),
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    #[inline]
+    fn new(scheme: TypeId) -> Self {
+        Self {
+            scheme,
+            retired_records: AtomicUsize::new(0),
+            retired_bytes: AtomicUsize::new(0),
+            reclaimed_records: AtomicUsize::new(0),
+            reclaimed_bytes: AtomicUsize::new(0),
+            live_guards: AtomicUsize::new(0),
+            next: ptr::null(),
+        }
+    }
+
+    #[inline]
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            retired_records: self.retired_records.load(Ordering::Relaxed),
+            retired_bytes: self.retired_bytes.load(Ordering::Relaxed),
+            reclaimed_records: self.reclaimed_records.load(Ordering::Relaxed),
+            reclaimed_bytes: self.reclaimed_bytes.load(Ordering::Relaxed),
+            live_guards: self.live_guards.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl Send for Stats {}
+unsafe impl Sync for Stats {}
+
+#[cfg(test)]
+mod tests {
+    use core::mem;
+    use core::sync::atomic::Ordering;
+
+    use typenum::U0;
+
+    use crate::leak::Leaking;
+    use crate::prelude::*;
+    use crate::Record;
+
+    use super::Instrumented;
+
+    type Atomic<T> = crate::Atomic<T, Instrumented<Leaking>, U0>;
+    type Owned<T> = crate::Owned<T, Instrumented<Leaking>, U0>;
+
+    #[test]
+    fn retired_and_guards() {
+        let before = Instrumented::<Leaking>::snapshot();
+
+        let atomic = Atomic::new([0u64; 4]);
+        let guard = Instrumented::<Leaking>::guard();
+        let clone = guard.clone();
+        let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+        assert_eq!(shared[0], 0);
+        assert_eq!(Instrumented::<Leaking>::snapshot().live_guards, before.live_guards + 2);
+
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+        drop((guard, clone));
+
+        let after = Instrumented::<Leaking>::snapshot();
+        assert_eq!(after.live_guards, before.live_guards);
+        assert_eq!(after.retired_records, before.retired_records + 1);
+        assert_eq!(
+            after.retired_bytes,
+            before.retired_bytes + mem::size_of::<Record<[u64; 4], Leaking>>()
+        );
+        assert_eq!(after.reclaimed_records, before.reclaimed_records);
+        assert_eq!(after.outstanding_records(), before.outstanding_records() + 1);
+    }
+
+    #[cfg(feature = "hazard")]
+    #[test]
+    fn reclaimed() {
+        use crate::hazard::Hazard;
+
+        type Atomic<T> = crate::Atomic<T, Instrumented<Hazard>, U0>;
+        type Owned<T> = crate::Owned<T, Instrumented<Hazard>, U0>;
+
+        let atomic = Atomic::new(1);
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+        Instrumented::<Hazard>::try_reclaim();
+
+        let snapshot = Instrumented::<Hazard>::snapshot();
+        assert_eq!(snapshot.retired_records, 1);
+        assert_eq!(snapshot.reclaimed_records, 1);
+        assert_eq!(snapshot.outstanding_bytes(), 0);
+    }
+}
@@ -0,0 +1,69 @@
+//! Lazily initialized global values for statics, which can not be constructed
+//! in a `const` context.
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{
+    AtomicPtr,
+    Ordering::{AcqRel, Acquire},
+};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Lazy
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A global value, which is allocated and initialized with its [`Default`]
+/// value on first access and is never de-allocated.
+///
+/// If multiple threads access the value before it is initialized, each of
+/// them may create a value of its own, but only one of them is ever kept.
+pub(crate) struct Lazy<T> {
+    ptr: AtomicPtr<T>,
+    _marker: PhantomData<T>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T> Lazy<T> {
+    /// Creates a new uninitialized value.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { ptr: AtomicPtr::new(ptr::null_mut()), _marker: PhantomData }
+    }
+}
+
+impl<T: Default> Lazy<T> {
+    /// Returns a reference to the value, initializing it first if necessary.
+    #[inline]
+    pub fn get(&self) -> &T {
+        match unsafe { self.ptr.load(Acquire).as_ref() } {
+            Some(value) => value,
+            None => self.init(),
+        }
+    }
+
+    #[cold]
+    fn init(&self) -> &T {
+        let value = Box::into_raw(Box::<T>::default());
+        match self.ptr.compare_exchange(ptr::null_mut(), value, AcqRel, Acquire) {
+            Ok(_) => unsafe { &*value },
+            Err(actual) => {
+                // another thread has initialized the value in the meantime
+                drop(unsafe { Box::from_raw(value) });
+                unsafe { &*actual }
+            }
+        }
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T: Default> Deref for Lazy<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
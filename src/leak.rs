@@ -11,6 +11,9 @@ use crate::{AcquireResult, GlobalReclaim, Protect, ProtectRegion, Reclaim};
 /// An [`Atomic`][crate::Atomic] type that uses the no-op [`Leaking`]
 /// "reclamation" scheme.
 pub type Atomic<T, N> = crate::Atomic<T, Leaking, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the no-op
+/// [`Leaking`] "reclamation" scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Leaking, N>;
 /// A [`Shared`][crate::Shared] type that uses the no-op [`Leaking`]
 /// "reclamation" scheme.
 pub type Shared<'g, T, N> = crate::Shared<'g, T, Leaking, N>;
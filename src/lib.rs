@@ -184,7 +184,32 @@ extern crate alloc;
 mod macros;
 
 pub mod align;
+pub mod allocator;
+#[cfg(feature = "std")]
+pub mod collector;
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod dst;
+#[cfg(feature = "epoch")]
+pub mod epoch;
+#[cfg(feature = "hazard")]
+pub mod hazard;
+#[cfg(feature = "hazard-eras")]
+pub mod hazard_eras;
+#[cfg(feature = "hyaline")]
+pub mod hyaline;
+#[cfg(feature = "ibr")]
+pub mod ibr;
+pub mod instrumented;
 pub mod leak;
+#[cfg(feature = "local-epoch")]
+pub mod local_epoch;
+#[cfg(all(feature = "nbr", target_os = "linux"))]
+pub mod nbr;
+#[cfg(feature = "qsbr")]
+pub mod qsbr;
+#[cfg(feature = "rc")]
+pub mod rc;
 pub mod prelude {
     //! Useful and/or required types, discriminants and traits for the `reclaim`
     //! crate.
@@ -200,14 +225,39 @@ pub mod prelude {
     pub use crate::Protect;
     pub use crate::ProtectRegion;
     pub use crate::Reclaim;
+    pub use crate::ReclaimDomain;
 }
 
 mod atomic;
 mod internal;
+#[cfg(any(
+    feature = "debug",
+    feature = "epoch",
+    feature = "hazard",
+    feature = "hazard-eras",
+    feature = "ibr",
+    all(feature = "nbr", target_os = "linux"),
+    feature = "pool",
+    feature = "qsbr",
+    feature = "rc"
+))]
+mod lazy;
 mod owned;
 mod pointer;
+#[cfg(any(
+    feature = "epoch",
+    feature = "hazard",
+    feature = "hazard-eras",
+    feature = "hyaline",
+    feature = "ibr",
+    all(feature = "nbr", target_os = "linux"),
+    feature = "qsbr"
+))]
+mod registry;
 mod retired;
 mod shared;
+#[cfg(test)]
+mod test_util;
 mod traits;
 mod unlinked;
 mod unprotected;
@@ -225,16 +275,22 @@ use core::sync::atomic::Ordering;
 // TODO: replace with const generics once available
 pub use typenum;
 
-use memoffset::offset_of;
 use typenum::Unsigned;
 
-pub use crate::atomic::{Atomic, CompareExchangeFailure};
+pub use crate::dst::Pointee;
+pub use crate::atomic::{
+    Atomic, CompareExchangeFailure, Decision, FetchUpdateResult, OwningAtomic, TagExchangeResult,
+};
 pub use crate::pointer::{
-    AtomicMarkedPtr, InvalidNullError, Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer,
-    MarkedPtr,
+    AtomicMarkedPair, AtomicMarkedPtr, InvalidNullError, Marked, MarkedNonNull, MarkedNonNullable,
+    MarkedPointer, MarkedPtr, PairExchangeFailure, RawPairExchangeResult,
 };
+#[cfg(target_pointer_width = "64")]
+pub use crate::pointer::{AtomicStampedPtr, StampedPtr};
 pub use crate::retired::Retired;
 
+use crate::retired::Deferred;
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // GlobalReclaim (trait)
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -295,6 +351,19 @@ where
     /// reclaim all retired records is likewise implementation dependent.
     fn try_reclaim();
 
+    /// Attempts to reclaim some retired records from a thread that holds no
+    /// references to any shared values, e.g. a background collector thread.
+    ///
+    /// Reclamation schemes that wait for the progress of all participating
+    /// threads can override this, so that the calling thread does not hold up
+    /// the reclamation of records retired by other threads.
+    /// The default implementation calls
+    /// [`try_reclaim`][GlobalReclaim::try_reclaim].
+    #[inline]
+    fn try_reclaim_quiescent() {
+        Self::try_reclaim();
+    }
+
     /// Retires a record and caches it **at least** until it is safe to
     /// deallocate it.
     ///
@@ -384,6 +453,124 @@ where
         debug_assert!(!ptr.is_null());
         Self::retire_unchecked(Unlinked::from_marked_ptr(ptr));
     }
+
+    /// Retires all records in `batch` and caches them **at least** until it is
+    /// safe to deallocate them.
+    ///
+    /// The default implementation retires each record individually.
+    /// Reclamation schemes can override it to amortize the synchronization
+    /// required for retiring a record over the entire batch, e.g. by stamping
+    /// all records with the same epoch.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats as with [`retire`][`GlobalReclaim::retire`] apply to
+    /// every record in `batch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering;
+    ///
+    /// use reclaim::leak::Leaking;
+    /// use reclaim::{GlobalReclaim, Owned};
+    ///
+    /// type Atomic<T> = reclaim::leak::Atomic<T, reclaim::typenum::U0>;
+    ///
+    /// let atomics = vec![Atomic::new(1), Atomic::new(2), Atomic::new(3)];
+    /// let unlinked =
+    ///     atomics.iter().filter_map(|atomic| atomic.swap(Owned::none(), Ordering::SeqCst));
+    /// unsafe { Leaking::retire_batch(unlinked) };
+    /// ```
+    unsafe fn retire_batch<T: 'static, N: Unsigned>(
+        batch: impl IntoIterator<Item = Unlinked<T, Self, N>>,
+    ) {
+        for unlinked in batch {
+            Self::retire(unlinked);
+        }
+    }
+
+    /// Defers the execution of `func` until the same point at which a record
+    /// retired instead would be reclaimed.
+    ///
+    /// The closure is stored in a separately allocated record, which is
+    /// retired like any other record, so it is executed **at least** after
+    /// all guards that existed when it was deferred have been released.
+    /// Like retired records, it may be executed by any thread or not at all,
+    /// e.g. by reclamation schemes that leak their records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use reclaim::leak::Leaking;
+    /// use reclaim::GlobalReclaim;
+    ///
+    /// Leaking::defer(|| println!("no reader can observe the old version"));
+    /// ```
+    #[inline]
+    fn defer(func: impl FnOnce() + Send + 'static) {
+        let owned = Owned::<_, Self, typenum::U0>::new(Deferred::new(func));
+        let unlinked = unsafe { Unlinked::from_marked_ptr(Owned::into_marked_ptr(owned)) };
+        unsafe { Self::retire(unlinked) };
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ReclaimDomain (trait)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A trait for reclamation domains, i.e. values which own an independent
+/// instance of the global state of a reclamation scheme.
+///
+/// Unlike the [`GlobalReclaim`] trait, which only consists of associated
+/// functions operating on a single process-wide state, guards are created
+/// from and records are retired into a specific domain.
+/// This allows isolating the retired records of e.g. a single data structure
+/// from all others and tearing down a domain together with the data structure
+/// using it.
+///
+/// Records retired into a domain are only protected by guards created from
+/// the same domain.
+/// Consequently, all threads accessing a data structure must use guards from
+/// the domain the data structure's records are retired into.
+///
+/// # Safety
+///
+/// The same requirements as for implementing [`GlobalReclaim`] apply to the
+/// domain's guards and retired records.
+pub unsafe trait ReclaimDomain {
+    /// The reclamation scheme of the domain.
+    type Reclaimer: Reclaim;
+    /// The type used for protecting concurrently shared references.
+    type Guard: Protect<Reclaimer = Self::Reclaimer>;
+
+    /// Creates a new [`Guard`][ReclaimDomain::Guard] for this domain.
+    fn guard(&self) -> Self::Guard;
+
+    /// Attempts to reclaim some of the records retired into this domain.
+    fn try_reclaim(&self);
+
+    /// Retires a record into this domain and caches it **at least** until it
+    /// is safe to deallocate it.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats as with [`retire_local`][`Reclaim::retire_local`]
+    /// apply.
+    /// Additionally, the record must not be protected by guards of any other
+    /// domain.
+    unsafe fn retire<T: 'static, N: Unsigned>(&self, unlinked: Unlinked<T, Self::Reclaimer, N>);
+
+    /// Retires a record into this domain and caches it **at least** until it
+    /// is safe to deallocate it.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats as with [`retire_local_unchecked`][`Reclaim::retire_local_unchecked`]
+    /// apply.
+    /// Additionally, the record must not be protected by guards of any other
+    /// domain.
+    unsafe fn retire_unchecked<T, N: Unsigned>(&self, unlinked: Unlinked<T, Self::Reclaimer, N>);
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -513,6 +700,61 @@ where
         debug_assert!(!ptr.is_null());
         Self::retire_local_unchecked(local, Unlinked::from_marked_ptr(ptr));
     }
+
+    /// Retires all records in `batch` and caches them **at least** until it is
+    /// safe to deallocate them.
+    ///
+    /// The default implementation retires each record individually.
+    /// Reclamation schemes can override it to amortize the synchronization
+    /// required for retiring a record over the entire batch, e.g. by stamping
+    /// all records with the same epoch.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats as with [`retire_local`][Reclaim::retire_local] apply
+    /// to every record in `batch`.
+    unsafe fn retire_local_batch<T: 'static, N: Unsigned>(
+        local: &Self::Local,
+        batch: impl IntoIterator<Item = Unlinked<T, Self, N>>,
+    ) {
+        for unlinked in batch {
+            Self::retire_local(local, unlinked);
+        }
+    }
+
+    /// Defers the execution of `func` until the same point at which a record
+    /// retired instead would be reclaimed.
+    ///
+    /// For further information, refer to the documentation of
+    /// [`defer`][GlobalReclaim::defer].
+    #[inline]
+    fn defer_local(local: &Self::Local, func: impl FnOnce() + Send + 'static) {
+        let owned = Owned::<_, Self, typenum::U0>::new(Deferred::new(func));
+        let unlinked = unsafe { Unlinked::from_marked_ptr(Owned::into_marked_ptr(owned)) };
+        unsafe { Self::retire_local(local, unlinked) };
+    }
+
+    /// Is called whenever a [`Shared`] reference to the record containing
+    /// `elem` is dereferenced.
+    ///
+    /// This hook allows reclamation schemes intended for debugging to detect
+    /// accesses to records that have already been reclaimed.
+    /// The default implementation does nothing.
+    #[inline]
+    fn on_access<T>(elem: NonNull<T>) {
+        let _ = elem;
+    }
+
+    /// Is called whenever a retired record containing `elem` is reclaimed,
+    /// right before it is dropped.
+    ///
+    /// This hook allows reclamation schemes wrapping other schemes to keep
+    /// track of reclaimed records.
+    /// The default implementation does nothing.
+    #[inline]
+    fn on_reclaim<T>(elem: NonNull<T>) {
+        let _ = elem;
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -723,6 +965,11 @@ impl Error for NotEqualError {}
 /// The record and its header are never directly exposed to the data structure
 /// using a given memory reclamation scheme and should only be accessed by the
 /// reclamation scheme itself.
+///
+/// Records have a `C` representation, so the offsets of the header and the
+/// element can be computed in constant contexts for any alignment and without
+/// relying on a (potentially unsound) `offset_of!` macro.
+#[repr(C)]
 pub struct Record<T, R: Reclaim> {
     /// The record's header
     header: R::RecordHeader,
@@ -819,29 +1066,19 @@ impl<T, R: Reclaim> Record<T, R> {
     /// Returns the offset in bytes from the address of a record to its header
     /// field.
     #[inline]
-    pub fn offset_header() -> usize {
-        // FIXME: the offset_of! macro is unsound, this allows at least avoiding using it
-        //        in many cases until a better solution becomes available
-        // https://internals.rust-lang.org/t/pre-rfc-add-a-new-offset-of-macro-to-core-mem/9273
-        if mem::size_of::<R::RecordHeader>() == 0 {
-            0
-        } else {
-            offset_of!(Self, header)
-        }
+    pub const fn offset_header() -> usize {
+        0
     }
 
     /// Returns the offset in bytes from the address of a record to its element
     /// field.
+    ///
+    /// Due to the record's `C` representation, the element is placed directly
+    /// after the header, rounded up to the element's alignment.
     #[inline]
-    pub fn offset_elem() -> usize {
-        // FIXME: the offset_of! macro is currently, this allows at least avoiding using it
-        //        in many cases until a better solution becomes available
-        // https://internals.rust-lang.org/t/pre-rfc-add-a-new-offset-of-macro-to-core-mem/9273
-        if mem::size_of::<R::RecordHeader>() == 0 {
-            0
-        } else {
-            offset_of!(Self, elem)
-        }
+    pub const fn offset_elem() -> usize {
+        let align = mem::align_of::<T>();
+        (mem::size_of::<R::RecordHeader>() + align - 1) & !(align - 1)
     }
 }
 
@@ -887,9 +1124,9 @@ impl<T, G: Protect, N: Unsigned> Guarded<T, G, N> {
 /// allocate the appropriate [`RecordHeader`][Reclaim::RecordHeader] type
 /// for its generic [`Reclaim`] parameter alongside their actual content.
 #[derive(Eq, Ord, PartialEq, PartialOrd)]
-pub struct Owned<T, R: Reclaim, N: Unsigned> {
+pub struct Owned<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> {
     inner: MarkedNonNull<T, N>,
-    _marker: PhantomData<(T, R)>,
+    _marker: PhantomData<(R, T)>,
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -905,7 +1142,7 @@ pub struct Owned<T, R: Reclaim, N: Unsigned> {
 /// they were originally read.
 /// They are also usually borrowed from guard values implementing the
 /// [`Protect`] trait.
-pub struct Shared<'g, T, R, N> {
+pub struct Shared<'g, T: ?Sized + Pointee, R, N> {
     inner: MarkedNonNull<T, N>,
     _marker: PhantomData<(&'g T, R)>,
 }
@@ -928,9 +1165,9 @@ pub struct Shared<'g, T, R, N> {
 /// in detail in the documentation for [`retire_local`][Reclaim::retire_local].
 #[derive(Eq, Ord, PartialEq, PartialOrd)]
 #[must_use = "unlinked values are meant to be retired, otherwise a memory leak is highly likely"]
-pub struct Unlinked<T, R, N> {
+pub struct Unlinked<T: ?Sized + Pointee, R, N> {
     inner: MarkedNonNull<T, N>,
-    _marker: PhantomData<(T, R)>,
+    _marker: PhantomData<(R, T)>,
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -946,7 +1183,72 @@ pub struct Unlinked<T, R, N> {
 /// Since are loaded from [`Atomic`] values they must (at least at one point)
 /// have been *valid* references.
 #[derive(Eq, Ord, PartialEq, PartialOrd)]
-pub struct Unprotected<T, R, N> {
+pub struct Unprotected<T: ?Sized + Pointee, R, N> {
     inner: MarkedNonNull<T, N>,
     _marker: PhantomData<R>,
 }
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+    use core::mem;
+    use core::ptr::NonNull;
+
+    use typenum::Unsigned;
+
+    use crate::align::{Aligned16, Aligned2, Aligned4, Aligned64, Aligned8};
+    use crate::{Reclaim, Record, Unlinked};
+
+    struct WithHeader<H>(PhantomData<H>);
+
+    unsafe impl<H: Default + Sync + 'static> Reclaim for WithHeader<H> {
+        type Local = ();
+        type RecordHeader = H;
+
+        unsafe fn retire_local<T: 'static, N: Unsigned>(_: &(), _: Unlinked<T, Self, N>) {
+            unreachable!()
+        }
+
+        unsafe fn retire_local_unchecked<T, N: Unsigned>(_: &(), _: Unlinked<T, Self, N>) {
+            unreachable!()
+        }
+    }
+
+    fn check_layout<H: Default + Sync + 'static, T: Default>() {
+        type R<H> = WithHeader<H>;
+
+        let mut record = Record::<T, R<H>>::new(T::default());
+        let base = &record as *const _ as usize;
+        let header = record.header() as *const _ as usize;
+        let elem = NonNull::from(&mut record.elem);
+
+        assert_eq!(header - base, Record::<T, R<H>>::offset_header());
+        assert_eq!(elem.as_ptr() as usize - base, Record::<T, R<H>>::offset_elem());
+        assert_eq!(elem.as_ptr() as usize % mem::align_of::<T>(), 0);
+
+        unsafe {
+            assert_eq!(Record::<T, R<H>>::from_raw_non_null(elem).as_ptr() as usize, base);
+            let from_raw = Record::<T, R<H>>::header_from_raw_non_null(elem) as *const H;
+            assert_eq!(from_raw as usize, header);
+        }
+    }
+
+    macro_rules! check_layouts {
+        ($($header:ty),*; $elems:tt) => {
+            $(check_layouts!(@elems $header; $elems);)*
+        };
+        (@elems $header:ty; [$($elem:ty),*]) => {
+            $(check_layout::<$header, $elem>();)*
+        };
+    }
+
+    #[test]
+    fn record_layout() {
+        check_layouts!(
+            (), u8, u16, [u8; 3], u64, Aligned2<u8>, Aligned4<[u8; 5]>, Aligned8<u8>,
+            Aligned16<[u8; 17]>, Aligned64<u8>;
+            [(), u8, u16, [u8; 3], u32, u64, u128, Aligned2<u8>, Aligned8<[u8; 9]>,
+             Aligned16<u8>, Aligned64<[u8; 3]>]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! The global state of the `no_std` epoch based reclamation scheme, which is
+//! shared by all participants.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cell::UnsafeCell;
+use core::mem;
+use core::sync::atomic::{self, AtomicBool, AtomicUsize, Ordering};
+
+use crate::retired::Retired;
+
+use super::LocalEpoch;
+
+/// The maximum number of participants that can be registered at the same
+/// time.
+pub const MAX_PARTICIPANTS: usize = 64;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global epoch counter and the fixed-capacity table of all participants.
+#[derive(Debug)]
+pub(crate) struct Global {
+    epoch: AtomicUsize,
+    participants: [Participant; MAX_PARTICIPANTS],
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state starting at epoch zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { epoch: AtomicUsize::new(0), participants: [Participant::UNUSED; MAX_PARTICIPANTS] }
+    }
+
+    /// Loads the current global epoch.
+    #[inline]
+    pub fn current_epoch(&self, order: Ordering) -> usize {
+        self.epoch.load(order)
+    }
+
+    /// Acquires an unused participant table entry for exclusive use or returns
+    /// [`None`] if all entries are in use.
+    #[inline]
+    pub fn register(&self) -> Option<&Participant> {
+        self.participants.iter().find(|participant| participant.try_acquire())
+    }
+
+    /// Attempts to advance the global epoch and returns the (possibly
+    /// updated) current epoch.
+    ///
+    /// The epoch can only be advanced if all currently pinned participants
+    /// have observed the current epoch.
+    #[inline]
+    pub fn try_advance(&self) -> usize {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        atomic::fence(Ordering::SeqCst);
+
+        for participant in self.participants.iter() {
+            if let Some(pinned) = participant.pinned_epoch(Ordering::Relaxed) {
+                if pinned != epoch {
+                    return epoch;
+                }
+            }
+        }
+
+        atomic::fence(Ordering::Acquire);
+        match self.epoch.compare_exchange(epoch, epoch + 1, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => epoch + 1,
+            Err(actual) => actual,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Participant
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An entry in the participant table, which contains the globally visible
+/// state of a participant and the records left behind by the previous
+/// participant using the same entry.
+#[derive(Debug)]
+pub(crate) struct Participant {
+    active: AtomicBool,
+    state: AtomicUsize,
+    abandoned: UnsafeCell<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Participant {
+    const PINNED_BIT: usize = 0b1;
+
+    /// An unused entry, which is only used for initializing the participant
+    /// table.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNUSED: Self = Self {
+        active: AtomicBool::new(false),
+        state: AtomicUsize::new(0),
+        abandoned: UnsafeCell::new(Vec::new()),
+    };
+
+    /// Announces the current global epoch and marks the participant as pinned.
+    #[inline]
+    pub fn pin(&self, global: &Global) {
+        let mut epoch = global.current_epoch(Ordering::Relaxed);
+        loop {
+            self.state.store((epoch << 1) | Self::PINNED_BIT, Ordering::Relaxed);
+            atomic::fence(Ordering::SeqCst);
+
+            // the global epoch may have been advanced before the announcement became visible
+            match global.current_epoch(Ordering::Relaxed) {
+                current if current == epoch => return,
+                current => epoch = current,
+            }
+        }
+    }
+
+    /// Marks the participant as no longer pinned.
+    #[inline]
+    pub fn unpin(&self) {
+        self.state.store(0, Ordering::Release);
+    }
+
+    /// Returns the announced epoch if the participant is currently pinned.
+    #[inline]
+    pub fn pinned_epoch(&self, order: Ordering) -> Option<usize> {
+        match self.state.load(order) {
+            state if state & Self::PINNED_BIT != 0 => Some(state >> 1),
+            _ => None,
+        }
+    }
+
+    /// Takes all records that were left behind by the previous participant
+    /// using this entry.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have acquired the entry.
+    #[inline]
+    pub unsafe fn adopt(&self) -> Vec<Sealed> {
+        mem::take(&mut *self.abandoned.get())
+    }
+
+    /// Leaves the given `records` behind in this entry and releases it, so it
+    /// can be acquired again by another participant.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have acquired the entry and must not use it any more
+    /// afterwards.
+    #[inline]
+    pub unsafe fn release(&self, records: Vec<Sealed>) {
+        *self.abandoned.get() = records;
+        self.active.store(false, Ordering::Release);
+    }
+
+    #[inline]
+    fn try_acquire(&self) -> bool {
+        !self.active.load(Ordering::Relaxed)
+            && self
+                .active
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+    }
+}
+
+/********** impl Sync *****************************************************************************/
+
+// the abandoned records are only ever accessed by the participant that has acquired the entry
+unsafe impl Sync for Participant {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Sealed
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record sealed with the global epoch at the time of its
+/// retirement.
+#[derive(Debug)]
+pub(crate) struct Sealed {
+    epoch: usize,
+    record: Retired<LocalEpoch>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Sealed {
+    /// Creates a new sealed record.
+    #[inline]
+    pub fn new(record: Retired<LocalEpoch>, epoch: usize) -> Self {
+        Self { epoch, record }
+    }
+
+    /// Returns `true` if the record can no longer be referenced by any
+    /// participant in the given `epoch`.
+    #[inline]
+    pub fn is_expired(&self, epoch: usize) -> bool {
+        epoch.wrapping_sub(self.epoch) >= 2
+    }
+
+    /// Reclaims the sealed record.
+    ///
+    /// # Safety
+    ///
+    /// The record must be expired and must not be reclaimed more than once.
+    #[inline]
+    pub unsafe fn reclaim(mut self) {
+        self.record.reclaim();
+    }
+
+    /// Removes all records that have expired in the given `epoch` from
+    /// `records` and returns them.
+    #[inline]
+    pub fn split_expired(records: &mut Vec<Sealed>, epoch: usize) -> Vec<Sealed> {
+        let (expired, remaining) = records.drain(..).partition(|sealed| sealed.is_expired(epoch));
+        *records = remaining;
+        expired
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any participant
+unsafe impl Send for Sealed {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Global, MAX_PARTICIPANTS};
+
+    #[test]
+    fn register_full() {
+        let global = Global::new();
+        let participants: Vec<_> = (0..MAX_PARTICIPANTS).map(|_| global.register()).collect();
+        assert!(participants.iter().all(Option::is_some));
+        assert!(global.register().is_none());
+
+        unsafe { participants[0].unwrap().release(Vec::new()) };
+        assert!(global.register().is_some());
+    }
+}
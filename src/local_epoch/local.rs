@@ -0,0 +1,145 @@
+//! The participant handle of the `no_std` epoch based reclamation scheme.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cell::{Cell, RefCell};
+use core::mem;
+use core::sync::atomic::{self, Ordering};
+
+use crate::retired::Retired;
+
+use super::global::{Global, Participant, Sealed};
+use super::{Guard, LocalEpoch, RegistryFullError, GLOBAL};
+
+/// The number of records a participant retires before it attempts to advance
+/// the global epoch and reclaim its expired records (must be a power of two).
+const RECLAIM_THRESHOLD: usize = 128;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An explicitly registered participant in the [`LocalEpoch`] reclamation
+/// scheme.
+///
+/// A handle occupies an entry in the global participant table until it is
+/// dropped.
+/// It can be sent to other threads, but not shared between them, so each
+/// thread or execution context requires its own handle.
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    participant: &'static Participant,
+    guard_count: Cell<usize>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Registers a new participant in the global participant table.
+    ///
+    /// Any records left behind by a previous participant using the same table
+    /// entry are adopted by the new participant.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if all entries of the participant table are currently in use.
+    #[inline]
+    pub fn register() -> Result<Self, RegistryFullError> {
+        let participant = GLOBAL.register().ok_or(RegistryFullError)?;
+        Ok(Self {
+            global: &GLOBAL,
+            participant,
+            guard_count: Cell::new(0),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(unsafe { participant.adopt() }),
+        })
+    }
+
+    /// Creates a new [`Guard`], which pins the participant.
+    #[inline]
+    pub fn guard(&self) -> Guard<'_> {
+        Guard::new(self)
+    }
+
+    /// Returns `true` if the participant is currently pinned by at least one
+    /// guard.
+    #[inline]
+    pub fn is_pinned(&self) -> bool {
+        self.guard_count.get() > 0
+    }
+
+    /// Attempts to advance the global epoch and reclaims all expired records
+    /// retired by this participant.
+    #[inline]
+    pub fn try_reclaim(&self) {
+        let epoch = self.global.try_advance();
+
+        // the records are taken out, because dropping them may retire further records
+        let expired = Sealed::split_expired(&mut self.retired.borrow_mut(), epoch);
+        for sealed in expired {
+            unsafe { sealed.reclaim() };
+        }
+    }
+
+    /// Increments the guard count and pins the participant, if it is not
+    /// already pinned.
+    #[inline]
+    pub(crate) fn pin(&self) {
+        let count = self.guard_count.get();
+        self.guard_count.set(count.checked_add(1).expect("guard count overflow"));
+
+        if count == 0 {
+            self.participant.pin(self.global);
+        }
+    }
+
+    /// Decrements the guard count and unpins the participant, if there are no
+    /// more guards left.
+    #[inline]
+    pub(crate) fn unpin(&self) {
+        let count = self.guard_count.get() - 1;
+        self.guard_count.set(count);
+
+        if count == 0 {
+            self.participant.unpin();
+        }
+    }
+
+    /// Seals the given `record` with the current global epoch and stores it
+    /// until it expires.
+    #[inline]
+    pub(crate) fn retire_record(&self, record: Retired<LocalEpoch>) {
+        // the epoch must be loaded *after* the record has been unlinked
+        atomic::fence(Ordering::SeqCst);
+        let epoch = self.global.current_epoch(Ordering::Relaxed);
+        self.retired.borrow_mut().push(Sealed::new(record, epoch));
+
+        let count = self.retire_count.get().wrapping_add(1);
+        self.retire_count.set(count);
+        if count & (RECLAIM_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// a handle can not be moved while any of its guards exist and retired records can be reclaimed by
+// any participant
+unsafe impl Send for Local {}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        self.try_reclaim();
+
+        let retired = mem::take(self.retired.get_mut());
+        unsafe { self.participant.release(retired) };
+    }
+}
@@ -0,0 +1,323 @@
+//! An epoch based memory reclamation scheme for `no_std` environments.
+//!
+//! This scheme works like the [`epoch`][epoch] scheme, but does not rely on
+//! thread local storage or any other facilities of the standard library.
+//! Instead, every thread (or other execution context) that wants to
+//! participate in the scheme has to explicitly [`register`][Local::register]
+//! a [`Local`] participant handle and pass it to every operation.
+//! Participants are stored in a global table with a fixed capacity of
+//! [`MAX_PARTICIPANTS`] entries, so registering fails once all entries are
+//! in use.
+//!
+//! Creating a [`Guard`] from a [`Local`] handle *pins* the participant, which
+//! announces the global epoch it has observed.
+//! While at least one guard exists, all values loaded through any guard of
+//! the same participant are protected from reclamation.
+//! Retired records are stored in the participant's local bag alongside the
+//! global epoch at the time of their retirement and can be reclaimed once the
+//! global epoch has been advanced twice since their retirement.
+//!
+//! Without the standard library, there are no locks for handing over records
+//! to a shared global state.
+//! Instead, records that are still not reclaimable when a [`Local`] is
+//! dropped are left behind in its table entry and are adopted by the next
+//! participant registering the same entry.
+//!
+//! [epoch]: https://docs.rs/reclaim/*/reclaim/epoch/index.html
+//!
+//! # Examples
+//!
+//! ```
+//! use core::sync::atomic::Ordering;
+//!
+//! use reclaim::local_epoch::{Local, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::local_epoch::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let local = Local::register().expect("participant table is full");
+//! let atomic = Atomic::new(1);
+//!
+//! let guard = local.guard();
+//! let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed before `guard` is dropped
+//!     unsafe { unlinked.retire_local(&local) };
+//! }
+//!
+//! assert_eq!(*shared, 1);
+//! drop(guard);
+//!
+//! local.try_reclaim();
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+
+pub use self::global::MAX_PARTICIPANTS;
+pub use self::local::Local;
+
+use core::fmt;
+use core::sync::atomic::Ordering;
+
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use typenum::Unsigned;
+
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::retired::Retired;
+use crate::{AcquireResult, NotEqualError, Protect, ProtectRegion, Reclaim};
+
+use self::global::Global;
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`LocalEpoch`]
+/// reclamation scheme.
+pub type Atomic<T, N> = crate::Atomic<T, LocalEpoch, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`LocalEpoch`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, LocalEpoch, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`LocalEpoch`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, LocalEpoch, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`LocalEpoch`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, LocalEpoch, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`LocalEpoch`]
+/// reclamation scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, LocalEpoch, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`LocalEpoch`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, LocalEpoch, N>;
+
+static GLOBAL: Global = Global::new();
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// LocalEpoch
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An epoch based memory reclamation scheme with explicit participant
+/// handles, which does not require the standard library.
+#[derive(Debug, Default)]
+pub struct LocalEpoch;
+
+/********** impl inherent *************************************************************************/
+
+impl LocalEpoch {
+    /// Converts an `unlinked` record into a type-erased [`Retired`] record.
+    #[inline]
+    unsafe fn into_retired<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> Retired<Self> {
+        Retired::new_unchecked(
+            MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null(),
+        )
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for LocalEpoch {
+    type Local = Local;
+    type RecordHeader = ();
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The guard type for the [`LocalEpoch`] reclamation scheme.
+///
+/// A guard pins the [`Local`] participant it is created from for as long as
+/// it exists and protects all values loaded during this time.
+#[derive(Debug)]
+pub struct Guard<'l> {
+    local: &'l Local,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'l> Guard<'l> {
+    /// Creates a new guard and pins the given `local` participant.
+    #[inline]
+    pub fn new(local: &'l Local) -> Self {
+        local.pin();
+        Self { local }
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard<'_> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.local)
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.local.unpin();
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard<'_> {
+    type Reclaimer = LocalEpoch;
+
+    /// This is a no-op, since the guard protects all values as long as it
+    /// exists.
+    #[inline]
+    fn release(&mut self) {}
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(atomic.load_raw(order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        match atomic.load_raw(order) {
+            raw if raw == expected => Ok(unsafe { Marked::from_marked_ptr(raw) }),
+            _ => Err(NotEqualError),
+        }
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl ProtectRegion for Guard<'_> {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RegistryFullError
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A zero-size marker type that represents the failure state of a
+/// [`register`][Local::register] operation, when all [`MAX_PARTICIPANTS`]
+/// entries of the participant table are in use.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct RegistryFullError;
+
+/********** impl Display **************************************************************************/
+
+impl fmt::Display for RegistryFullError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "all {} participant table entries are in use.", MAX_PARTICIPANTS)
+    }
+}
+
+/********** impl Error ****************************************************************************/
+
+#[cfg(feature = "std")]
+impl Error for RegistryFullError {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use typenum::U0;
+
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, Local};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    #[test]
+    fn pin_nested() {
+        let local = Local::register().unwrap();
+        assert!(!local.is_pinned());
+        let guard = Guard::new(&local);
+        let clone = guard.clone();
+        drop(guard);
+        assert!(local.is_pinned());
+        drop(clone);
+        assert!(!local.is_pinned());
+    }
+
+    #[test]
+    fn reclaim_after_unpin() {
+        let local = Local::register().unwrap();
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let guard = local.guard();
+        let shared = atomic.load(Ordering::Relaxed, &guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        unsafe { unlinked.retire_local(&local) };
+
+        for _ in 0..10 {
+            local.try_reclaim();
+        }
+
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        drop(guard);
+        test_util::reclaim_until(&count, 1, || local.try_reclaim());
+    }
+
+    #[test]
+    fn adopt_abandoned() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Arc::new(Atomic::null());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let count = Arc::clone(&count);
+                let atomic = Arc::clone(&atomic);
+                thread::spawn(move || {
+                    let local = Local::register().unwrap();
+                    for _ in 0..100 {
+                        let owned = Owned::new(DropCount(Arc::clone(&count)));
+                        if let Some(unlinked) = atomic.swap(owned, Ordering::AcqRel) {
+                            unsafe { unlinked.retire_local(&local) };
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut atomic = Arc::try_unwrap(atomic).unwrap();
+        drop(atomic.take());
+
+        // abandoned records are adopted by the participants re-using their entries
+        test_util::reclaim_until(&count, 400, || {
+            let locals: Vec<_> = (0..16).filter_map(|_| Local::register().ok()).collect();
+            locals.iter().for_each(Local::try_reclaim);
+        });
+    }
+}
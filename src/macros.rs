@@ -32,9 +32,9 @@ macro_rules! impl_trait {
 
         #[inline]
         fn decompose($self: Self) -> (Self, usize) {
-            let (inner, tag) = $self.inner.decompose();
+            let (inner, tag) = ($self.inner.clear_tag(), $self.inner.decompose_tag());
             core::mem::forget($self);
-            ( Self { inner: crate::pointer::MarkedNonNull::from(inner), _marker: PhantomData }, tag)
+            (Self { inner, _marker: PhantomData }, tag)
         }
 
         #[inline]
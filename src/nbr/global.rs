@@ -0,0 +1,285 @@
+//! The global state of the neutralization based reclamation scheme, which is
+//! shared by all threads.
+
+use std::ptr;
+use std::sync::atomic::{self, AtomicI32, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::registry::{Entry, Registry};
+use crate::retired::Retired;
+
+use super::signal;
+use super::Nbr;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The registry of all participating threads and their reservations and the
+/// records that were left behind by exiting threads.
+#[derive(Debug)]
+pub(crate) struct Global {
+    participants: Registry<Participant>,
+    reservations: Registry<Reservation>,
+    abandoned: Mutex<Abandoned>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state without any participants.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            participants: Registry::new(),
+            reservations: Registry::new(),
+            abandoned: Mutex::new(Abandoned(Vec::new())),
+        }
+    }
+
+    /// Registers the calling thread as a new participant, which is initially
+    /// not in a read phase.
+    #[inline]
+    pub fn register(&self) -> &Entry<Participant> {
+        let participant = self.participants.acquire();
+        participant.tid.store(signal::current_tid(), Ordering::Relaxed);
+        participant
+    }
+
+    /// Acquires an unused reservation for exclusive use by the calling thread.
+    #[inline]
+    pub fn acquire_reservation(&self) -> &Entry<Reservation> {
+        self.reservations.acquire()
+    }
+
+    /// Sends the neutralization signal to all other participants that are
+    /// currently in a read phase and returns the read phases that have to be
+    /// left before any record retired so far can be reclaimed.
+    #[inline]
+    pub fn neutralize_readers(&self, current: &Participant) -> Vec<Reader<'_>> {
+        // synchronizes with the fence in `Participant::begin_phase`
+        atomic::fence(Ordering::SeqCst);
+
+        let mut readers = Vec::new();
+        for participant in self.participants.iter() {
+            if let Some(seq) = participant.reading_seq(Ordering::Relaxed) {
+                if !ptr::eq(&**participant, current) {
+                    // the thread may have exited in the meantime, in which case it has also left
+                    // its read phase
+                    let _ = signal::send(participant.tid.load(Ordering::Relaxed));
+                }
+
+                readers.push(Reader { participant, seq });
+            }
+        }
+
+        readers
+    }
+
+    /// Collects the addresses of all records that are currently reserved by
+    /// any thread into a sorted vector.
+    #[inline]
+    pub fn collect_reserved(&self) -> Vec<*const ()> {
+        // synchronizes with the fence in `Reservation::reserve`
+        atomic::fence(Ordering::SeqCst);
+
+        let mut reserved: Vec<_> = self
+            .reservations
+            .iter()
+            .filter_map(|reservation| reservation.reserved(Ordering::Relaxed))
+            .collect();
+        reserved.sort_unstable();
+        reserved
+    }
+
+    /// Hands over all records in `records` to the global state, from where
+    /// they can eventually be reclaimed by any other thread.
+    #[inline]
+    pub fn abandon(&self, records: impl IntoIterator<Item = Retired<Nbr>>) {
+        let mut abandoned = self.abandoned.lock().unwrap_or_else(|err| err.into_inner());
+        abandoned.0.extend(records);
+    }
+
+    /// Moves all abandoned records into `records`.
+    ///
+    /// If another thread is currently accessing the abandoned records, this is
+    /// a no-op.
+    #[inline]
+    pub fn adopt_abandoned(&self, records: &mut Vec<Retired<Nbr>>) {
+        if let Ok(mut abandoned) = self.abandoned.try_lock() {
+            records.append(&mut abandoned.0);
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Global {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline]
+    fn drop(&mut self) {
+        let abandoned = self.abandoned.get_mut().unwrap_or_else(|err| err.into_inner());
+        for mut retired in abandoned.0.drain(..) {
+            unsafe { retired.reclaim() };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Participant
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The globally visible state of a participating thread.
+///
+/// The state is only ever modified by the participating thread itself,
+/// either directly or from within its neutralization signal handler.
+#[derive(Debug, Default)]
+pub(crate) struct Participant {
+    state: AtomicUsize,
+    tid: AtomicI32,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Participant {
+    const READING_BIT: usize = 0b01;
+    const NEUTRALIZED_BIT: usize = 0b10;
+    const SEQ_SHIFT: usize = 2;
+
+    /// Begins a new read phase and returns its sequence number.
+    #[inline]
+    pub fn begin_phase(&self) -> usize {
+        let seq = (self.state.load(Ordering::Relaxed) >> Self::SEQ_SHIFT).wrapping_add(1);
+        self.state.store((seq << Self::SEQ_SHIFT) | Self::READING_BIT, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        seq
+    }
+
+    /// Ends the current read phase.
+    #[inline]
+    pub fn end_phase(&self) {
+        let seq = self.state.load(Ordering::Relaxed) >> Self::SEQ_SHIFT;
+        self.state.store(seq << Self::SEQ_SHIFT, Ordering::Release);
+    }
+
+    /// Returns `true` if the current read phase has been neutralized.
+    #[inline]
+    pub fn is_neutralized(&self) -> bool {
+        // the flag is set by a signal handler running on the same thread
+        atomic::compiler_fence(Ordering::SeqCst);
+        self.state.load(Ordering::Relaxed) & Self::NEUTRALIZED_BIT != 0
+    }
+
+    /// Marks the current read phase as neutralized, if there is one.
+    ///
+    /// Must only be called from the participating thread's signal handler.
+    #[inline]
+    pub fn neutralize(&self) {
+        let state = self.state.load(Ordering::Relaxed);
+        if state & Self::READING_BIT != 0 {
+            self.state.store(state | Self::NEUTRALIZED_BIT, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the sequence number of the current read phase, if the
+    /// participant is in one.
+    #[inline]
+    pub fn reading_seq(&self, order: Ordering) -> Option<usize> {
+        match self.state.load(order) {
+            state if state & Self::READING_BIT != 0 => Some(state >> Self::SEQ_SHIFT),
+            _ => None,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Reader
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A read phase observed by a reclaiming thread.
+#[derive(Debug)]
+pub(crate) struct Reader<'g> {
+    participant: &'g Participant,
+    seq: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Reader<'_> {
+    /// Returns `true` if the participant has left (or restarted) the observed
+    /// read phase.
+    #[inline]
+    pub fn has_left(&self) -> bool {
+        self.participant.reading_seq(Ordering::Acquire) != Some(self.seq)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Reservation
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A globally visible pointer to a record, which must not be reclaimed as
+/// long as it is reserved.
+#[derive(Debug)]
+pub(crate) struct Reservation {
+    reserved: AtomicPtr<()>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Reservation {
+    /// Publishes the given record address as reserved.
+    ///
+    /// The reservation is only established once the caller has validated
+    /// that the record has not been unlinked or that its read phase has not
+    /// been neutralized in the meantime.
+    #[inline]
+    pub fn reserve(&self, record: *const ()) {
+        self.reserved.store(record as *mut (), Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Clears any current reservation.
+    #[inline]
+    pub fn clear(&self) {
+        self.reserved.store(ptr::null_mut(), Ordering::Release);
+    }
+
+    /// Returns the currently reserved record address, if there is any.
+    #[inline]
+    pub fn reserved(&self, order: Ordering) -> Option<*const ()> {
+        match self.reserved.load(order) {
+            ptr if ptr.is_null() => None,
+            ptr => Some(ptr as *const ()),
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Reservation {
+    #[inline]
+    fn default() -> Self {
+        Self { reserved: AtomicPtr::new(ptr::null_mut()) }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Abandoned
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+struct Abandoned(Vec<Retired<Nbr>>);
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Abandoned {}
@@ -0,0 +1,238 @@
+//! The thread local state of the neutralization based reclamation scheme.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::ptr;
+
+use crate::registry::Entry;
+use crate::retired::Retired;
+
+use super::global::{Global, Participant, Reader, Reservation};
+use super::{signal, Nbr};
+
+/// The number of records a thread retires before it attempts to neutralize
+/// all readers and reclaim its unreserved records (must be a power of two).
+const RECLAIM_THRESHOLD: usize = 64;
+/// The maximum number of released reservations each thread keeps cached for
+/// later re-use.
+const RESERVATION_CACHE_SIZE: usize = 16;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`Nbr`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first enters a
+/// read phase, creates a [`Guard`][super::Guard] or retires a record through
+/// the [`GlobalReclaim`] interface.
+/// The instance of the current thread can be accessed through
+/// [`Nbr::with_local`][super::Nbr::with_local].
+///
+/// [`GlobalReclaim`]: crate::GlobalReclaim
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    participant: &'static Entry<Participant>,
+    reservation_cache: RefCell<Vec<&'static Entry<Reservation>>>,
+    in_phase: Cell<bool>,
+    next_token: Cell<u64>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Retired<Nbr>>>,
+    round: RefCell<Option<Round>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state, registers it as participant in the global
+    /// state and installs the neutralization signal handler.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        signal::install_handler();
+        let participant = global.register();
+        signal::set_current(&**participant);
+
+        Self {
+            global,
+            participant,
+            reservation_cache: RefCell::new(Vec::new()),
+            in_phase: Cell::new(false),
+            next_token: Cell::new(1),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(Vec::new()),
+            round: RefCell::new(None),
+        }
+    }
+
+    /// Returns `true` if the thread is currently in a read phase.
+    #[inline]
+    pub fn is_reading(&self) -> bool {
+        self.in_phase.get()
+    }
+
+    /// Begins a new read phase and returns its sequence number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread is already in a read phase.
+    #[inline]
+    pub(crate) fn begin_phase(&self) -> usize {
+        assert!(!self.in_phase.replace(true), "read phases must not be nested");
+        self.participant.begin_phase()
+    }
+
+    /// Ends the current read phase.
+    #[inline]
+    pub(crate) fn end_phase(&self) {
+        self.participant.end_phase();
+        self.in_phase.set(false);
+    }
+
+    /// Returns `true` if the current read phase has been neutralized.
+    #[inline]
+    pub(crate) fn is_neutralized(&self) -> bool {
+        self.participant.is_neutralized()
+    }
+
+    /// Returns a new token for identifying a reservation, which is unique
+    /// within this thread.
+    #[inline]
+    pub(crate) fn next_token(&self) -> u64 {
+        let token = self.next_token.get();
+        self.next_token.set(token + 1);
+        token
+    }
+
+    /// Acquires a reservation, preferably from the thread local cache.
+    #[inline]
+    pub(crate) fn acquire_reservation(&self) -> &'static Entry<Reservation> {
+        match self.reservation_cache.borrow_mut().pop() {
+            Some(reservation) => reservation,
+            None => self.global.acquire_reservation(),
+        }
+    }
+
+    /// Clears and returns the `reservation` to the thread local cache or
+    /// releases it globally, if the cache is full.
+    #[inline]
+    pub(crate) fn release_reservation(&self, reservation: &'static Entry<Reservation>) {
+        reservation.clear();
+        let mut cache = self.reservation_cache.borrow_mut();
+        if cache.len() < RESERVATION_CACHE_SIZE {
+            cache.push(reservation);
+        } else {
+            reservation.release();
+        }
+    }
+
+    /// Stores the given `record` until it can be reclaimed.
+    #[inline]
+    pub(crate) fn retire_record(&self, record: Retired<Nbr>) {
+        self.retired.borrow_mut().push(record);
+
+        let count = self.retire_count.get().wrapping_add(1);
+        self.retire_count.set(count);
+        if count & (RECLAIM_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+
+    /// Reclaims all unreserved records of the current neutralization round,
+    /// if all neutralized readers have left their read phases, and starts a
+    /// new round for all records retired since.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        let completed = {
+            let mut round = self.round.borrow_mut();
+            match &*round {
+                Some(current) if current.is_complete() => round.take(),
+                _ => None,
+            }
+        };
+
+        if let Some(round) = completed {
+            let reserved = self.global.collect_reserved();
+            let (unreserved, reserved): (Vec<_>, Vec<_>) = round
+                .records
+                .into_iter()
+                .partition(|retired| reserved.binary_search(&retired.as_ptr()).is_err());
+            self.retired.borrow_mut().extend(reserved);
+
+            // the records are reclaimed without any borrows, because dropping them may retire
+            // further records
+            for mut retired in unreserved {
+                unsafe { retired.reclaim() };
+            }
+        }
+
+        if self.round.borrow().is_none() {
+            self.start_round();
+        }
+    }
+
+    /// Starts a new neutralization round for all records retired by this
+    /// thread or abandoned by exited threads.
+    #[inline]
+    fn start_round(&self) {
+        let records = {
+            let mut retired = self.retired.borrow_mut();
+            self.global.adopt_abandoned(&mut retired);
+            mem::take(&mut *retired)
+        };
+
+        if !records.is_empty() {
+            let readers = self.global.neutralize_readers(self.participant);
+            *self.round.borrow_mut() = Some(Round { records, readers });
+        }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        signal::set_current(ptr::null());
+        for reservation in self.reservation_cache.get_mut().drain(..) {
+            reservation.release();
+        }
+
+        self.try_reclaim();
+        self.participant.release();
+
+        let mut retired = mem::take(self.retired.get_mut());
+        if let Some(round) = self.round.get_mut().take() {
+            retired.extend(round.records);
+        }
+
+        if !retired.is_empty() {
+            self.global.abandon(retired);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Round
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A set of retired records and all read phases that were neutralized after
+/// the records had been retired.
+#[derive(Debug)]
+struct Round {
+    records: Vec<Retired<Nbr>>,
+    readers: Vec<Reader<'static>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Round {
+    /// Returns `true` if all neutralized readers have left their read phases,
+    /// so that none of them can still hold an unreserved reference to any of
+    /// the round's records.
+    #[inline]
+    fn is_complete(&self) -> bool {
+        self.readers.iter().all(Reader::has_left)
+    }
+}
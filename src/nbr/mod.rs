@@ -0,0 +1,630 @@
+//! A neutralization based memory reclamation scheme (NBR) for Linux.
+//!
+//! Threads access shared memory in two kinds of phases:
+//!
+//! - In a *read phase* (see [`Nbr::read`]), values can be loaded without any
+//!   per-value protection overhead, similar to epoch based schemes.
+//!   Read phases are *restartable*: once a reclaiming thread neutralizes a
+//!   read phase, all further loads and checks within it fail with
+//!   [`Neutralized`] and the phase is restarted from its beginning (the
+//!   *checkpoint*).
+//!   Neutralization is cooperative, i.e. a neutralized read phase is only
+//!   restarted once it performs its next load or check.
+//! - In a *write phase*, i.e. outside of read phases, only the few values
+//!   that have been explicitly *reserved* in a [`Guard`] can be accessed.
+//!   Values are either reserved at the end of a read phase or acquired
+//!   directly through a guard, which works like a hazard pointer.
+//!
+//! When a thread has retired enough records, it sends a POSIX signal to all
+//! threads currently in a read phase.
+//! The signal handler marks the interrupted read phase as neutralized.
+//! The retired records are reclaimed once all neutralized threads have left
+//! their read phases, except for those records that are still reserved by
+//! any guard.
+//!
+//! Note, that a thread that stalls in a read phase without loading or
+//! checking any values never leaves it and thereby prevents any further
+//! reclamation by all threads that have neutralized it.
+//! The amount of memory that can not be reclaimed is hence only bounded as
+//! long as read phases regularly access shared memory.
+//!
+//! The scheme uses the first real-time signal (`SIGRTMIN`) and installs a
+//! process-wide handler for it, which restarts interrupted system calls.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::nbr::{Guard, Nbr, Owned};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::nbr::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//! let mut guard = Guard::new();
+//!
+//! // the read phase is restarted from here, if it is neutralized
+//! let reserved = Nbr::read(|phase| {
+//!     let shared = phase.load(&atomic, Ordering::Acquire)?.unwrap_value();
+//!     assert_eq!(*shared, 1);
+//!     phase.reserve(&mut guard, shared)
+//! });
+//!
+//! // the write phase can only access reserved values
+//! let shared = guard.get(reserved).unwrap();
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is not reclaimed while `guard` reserves it
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! Nbr::try_reclaim();
+//! assert_eq!(*shared, 1);
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+mod signal;
+
+pub use self::local::Local;
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use std::error::Error;
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::registry::Entry;
+use crate::retired::Retired;
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, Reclaim, Record};
+
+use self::global::{Global, Reservation};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Nbr`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Nbr, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Nbr`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Nbr, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Nbr`] reclamation scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Nbr, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Nbr`] reclamation scheme.
+pub type Owned<T, N> = crate::Owned<T, Nbr, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Nbr`] reclamation
+/// scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Nbr, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Nbr`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Nbr, N>;
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Nbr
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A neutralization based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct Nbr;
+
+/********** impl inherent *************************************************************************/
+
+impl Nbr {
+    /// Executes `func` in a read phase and returns its result.
+    ///
+    /// Whenever the read phase is neutralized, `func` eventually returns an
+    /// [`Err(Neutralized)`][Neutralized] result (by propagating the error of
+    /// a load or check) and is then called again in a new read phase.
+    /// Consequently, any side effects of `func` must be idempotent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within another read phase or if the current
+    /// thread's local storage has already been destroyed.
+    #[inline]
+    pub fn read<T>(mut func: impl FnMut(&ReadPhase<'_>) -> Result<T, Neutralized>) -> T {
+        LOCAL.with(|local| loop {
+            let phase = ReadPhase::new(local);
+            if let Ok(res) = func(&phase) {
+                return res;
+            }
+        })
+    }
+
+    /// Returns `true` if the current thread is in a read phase.
+    #[inline]
+    pub fn is_reading() -> bool {
+        LOCAL.with(Local::is_reading)
+    }
+
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Converts an `unlinked` record into a type-erased [`Retired`] record.
+    #[inline]
+    unsafe fn into_retired<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> Retired<Self> {
+        Retired::new_unchecked(
+            MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null(),
+        )
+    }
+
+    /// Retires the `record` in the local state of the current thread or, if
+    /// the thread local storage has already been destroyed, directly abandons
+    /// it to the global state.
+    #[inline]
+    fn retire_record(record: Retired<Self>) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => unsafe { (*local).retire_record(record) },
+            Err(_) => GLOBAL.abandon(Some(record)),
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Nbr {
+    type Guard = Guard;
+
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Nbr {
+    type Local = Local;
+    type RecordHeader = ();
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ReadPhase
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A restartable read phase of the current thread.
+///
+/// All values loaded through a read phase remain valid until the phase ends,
+/// but any load or check fails once the phase has been neutralized.
+#[derive(Debug)]
+pub struct ReadPhase<'l> {
+    local: &'l Local,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'l> ReadPhase<'l> {
+    #[inline]
+    fn new(local: &'l Local) -> Self {
+        local.begin_phase();
+        Self { local }
+    }
+
+    /// Checks whether the read phase has been neutralized.
+    ///
+    /// Long running read phases that do not load any values should call this
+    /// regularly.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the read phase has been neutralized.
+    #[inline]
+    pub fn check(&self) -> Result<(), Neutralized> {
+        match self.local.is_neutralized() {
+            true => Err(Neutralized),
+            false => Ok(()),
+        }
+    }
+
+    /// Loads a value from `atomic`, which remains valid until the end of the
+    /// read phase.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the read phase has been neutralized.
+    #[inline]
+    pub fn load<T, N: Unsigned>(
+        &self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Result<Marked<Shared<'_, T, N>>, Neutralized> {
+        let marked = atomic.load_raw(order);
+        self.check()?;
+        Ok(unsafe { Marked::from_marked_ptr(marked) })
+    }
+
+    /// Reserves the value of `shared` in `guard`, so it can still be accessed
+    /// after the read phase has ended.
+    ///
+    /// Any value previously protected by `guard` is released.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the read phase has been neutralized, in which case `guard`
+    /// is released.
+    #[inline]
+    pub fn reserve<T, N: Unsigned>(
+        &self,
+        guard: &mut Guard,
+        shared: Shared<'_, T, N>,
+    ) -> Result<Reserved<T, N>, Neutralized> {
+        let ptr = MarkedNonNullable::into_marked_non_null(shared);
+        guard.publish(ptr.decompose_ptr());
+
+        // the phase may have been neutralized before the reservation became visible
+        if let Err(err) = self.check() {
+            guard.release();
+            return Err(err);
+        }
+
+        guard.token = self.local.next_token();
+        Ok(Reserved { ptr, token: guard.token, _marker: PhantomData })
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for ReadPhase<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.local.end_phase();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Reserved
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A handle to a value reserved in a [`Guard`] during a read phase, which can
+/// be used to access the value from the guard after the read phase has
+/// ended.
+#[derive(Debug)]
+pub struct Reserved<T, N: Unsigned> {
+    ptr: MarkedNonNull<T, N>,
+    token: u64,
+    // reservation tokens are only unique within a single thread
+    _marker: PhantomData<*const ()>,
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<T, N: Unsigned> Clone for Reserved<T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/********** impl Copy *****************************************************************************/
+
+impl<T, N: Unsigned> Copy for Reserved<T, N> {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Nbr`] reclamation
+/// scheme.
+///
+/// Each guard owns a reservation for as long as it exists and can protect a
+/// single value at a time, either by reserving it at the end of a read phase
+/// or by acquiring it directly like a hazard pointer.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+    reservation: &'static Entry<Reservation>,
+    token: u64,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard owning a reservation, which does not yet protect
+    /// any value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| Self {
+            local: NonNull::from(local),
+            reservation: local.acquire_reservation(),
+            token: 0,
+        })
+    }
+
+    /// Returns the value `reserved` by this guard, if it has not since been
+    /// replaced or released.
+    #[inline]
+    pub fn get<T, N: Unsigned>(&self, reserved: Reserved<T, N>) -> Option<Shared<'_, T, N>> {
+        match reserved.token {
+            token if token == self.token => {
+                Some(unsafe { Shared::from_marked_non_null(reserved.ptr) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Publishes the address of the record containing `ptr` in the guard's
+    /// reservation and invalidates all previous reservations.
+    #[inline]
+    fn publish<T>(&mut self, ptr: *mut T) {
+        // reservations are matched against the addresses of retired records, which must hence
+        // be the same for the record and its element, even for records retired with a custom
+        // deleter
+        debug_assert_eq!(Record::<T, Nbr>::offset_elem(), 0);
+        let record = unsafe { Record::<T, Nbr>::from_raw(ptr) };
+        self.reservation.reserve(record.as_ptr() as *const ());
+        self.token = 0;
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    /// Creates a new guard with its own reservation, which protects the same
+    /// value as `self`.
+    #[inline]
+    fn clone(&self) -> Self {
+        let local = unsafe { self.local.as_ref() };
+        let reservation = local.acquire_reservation();
+        // the value is still protected by `self`, so no validation is required
+        if let Some(reserved) = self.reservation.reserved(Ordering::Relaxed) {
+            reservation.reserve(reserved);
+        }
+
+        Self { local: self.local, reservation, token: 0 }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().release_reservation(self.reservation) };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Nbr;
+
+    #[inline]
+    fn release(&mut self) {
+        self.reservation.clear();
+        self.token = 0;
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        let mut marked = atomic.load_raw(Ordering::Relaxed);
+        loop {
+            let ptr = match MarkedNonNull::new(marked) {
+                Marked::Value(ptr) => ptr,
+                Marked::Null(tag) => {
+                    self.release();
+                    return Marked::Null(tag);
+                }
+            };
+
+            self.publish(ptr.decompose_ptr());
+
+            // the value may have been unlinked before the reservation became visible
+            let current = atomic.load_raw(order);
+            if current.decompose_ptr() == ptr.decompose_ptr() {
+                return unsafe { Marked::from_marked_ptr(current) };
+            }
+
+            marked = current;
+        }
+    }
+
+    /// Acquires a value from shared memory if it equals `expected`.
+    ///
+    /// If the value changes after the initial comparison but before the
+    /// protection could be validated, the guard is released and no value is
+    /// protected by it afterwards.
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        if atomic.load_raw(Ordering::Relaxed) != expected {
+            return Err(NotEqualError);
+        }
+
+        if expected.is_null() {
+            self.release();
+            return Ok(Marked::Null(expected.decompose_tag()));
+        }
+
+        self.publish(expected.decompose_ptr());
+        if atomic.load_raw(order) == expected {
+            Ok(unsafe { Marked::from_marked_ptr(expected) })
+        } else {
+            self.release();
+            Err(NotEqualError)
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Neutralized
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A zero-size marker type that represents the failure state of an operation
+/// in a [`ReadPhase`] that has been neutralized.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Neutralized;
+
+/********** impl Display **************************************************************************/
+
+impl fmt::Display for Neutralized {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "read phase has been neutralized.")
+    }
+}
+
+/********** impl Error ****************************************************************************/
+
+impl Error for Neutralized {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, Nbr};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    fn reclaim_until(count: &AtomicUsize, expected: usize) {
+        test_util::reclaim_until(count, expected, Nbr::try_reclaim);
+    }
+
+    #[test]
+    fn reserved_not_reclaimed() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let reserved = Nbr::read(|phase| {
+            assert!(Nbr::is_reading());
+            let shared = phase.load(&atomic, Ordering::Acquire)?.unwrap_value();
+            phase.reserve(&mut guard, shared)
+        });
+
+        assert!(!Nbr::is_reading());
+        let shared = guard.get(reserved).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        for _ in 0..10 {
+            Nbr::try_reclaim();
+        }
+
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        guard.release();
+        assert!(guard.get(reserved).is_none());
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn neutralize_reader() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Arc::new(Atomic::new(DropCount(Arc::clone(&count))));
+        let reading = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let atomic = Arc::clone(&atomic);
+            let reading = Arc::clone(&reading);
+            thread::spawn(move || {
+                let mut attempts = 0;
+                Nbr::read(|phase| {
+                    attempts += 1;
+                    let _ = phase.load(&atomic, Ordering::Acquire)?;
+                    if attempts == 1 {
+                        reading.store(true, Ordering::Release);
+                        // spins until the read phase is neutralized
+                        loop {
+                            phase.check()?;
+                            thread::yield_now();
+                        }
+                    }
+
+                    Ok(())
+                });
+
+                attempts
+            })
+        };
+
+        while !reading.load(Ordering::Acquire) {
+            thread::yield_now();
+        }
+
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+        Nbr::try_reclaim();
+
+        assert_eq!(handle.join().unwrap(), 2);
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn reclaim_abandoned() {
+        let count = test_util::retire_from_threads::<Nbr>(4, 100);
+        reclaim_until(&count, 400);
+    }
+}
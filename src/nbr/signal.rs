@@ -0,0 +1,82 @@
+//! The POSIX signal handling of the neutralization based reclamation scheme.
+
+// recent `libc` versions re-export `c_int` from `core::ffi`, older ones define it themselves
+#![allow(clippy::incompatible_msrv)]
+
+use std::cell::Cell;
+use std::mem;
+use std::ptr;
+use std::sync::Once;
+
+use libc::{c_int, pid_t};
+
+use super::global::Participant;
+
+thread_local!(static CURRENT: Cell<*const Participant> = const { Cell::new(ptr::null()) });
+
+static INSTALL: Once = Once::new();
+
+/// Returns the signal number used for neutralizing threads, which is the
+/// first real-time signal available to applications.
+#[inline]
+pub fn neutralization_signal() -> c_int {
+    libc::SIGRTMIN()
+}
+
+/// Installs the process-wide neutralization signal handler, if it has not
+/// been installed yet.
+///
+/// # Panics
+///
+/// Panics if the signal handler can not be installed.
+#[inline]
+pub fn install_handler() {
+    INSTALL.call_once(|| unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = handle_neutralization as extern "C" fn(c_int) as usize;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+
+        let res = libc::sigaction(neutralization_signal(), &action, ptr::null_mut());
+        assert_eq!(res, 0, "failed to install neutralization signal handler");
+    });
+}
+
+/// Sets the participant that is neutralized when the calling thread receives
+/// the neutralization signal.
+#[inline]
+pub fn set_current(participant: *const Participant) {
+    let _ = CURRENT.try_with(|current| current.set(participant));
+}
+
+/// Returns the kernel thread id of the calling thread.
+#[inline]
+pub fn current_tid() -> pid_t {
+    unsafe { libc::syscall(libc::SYS_gettid) as pid_t }
+}
+
+/// Sends the neutralization signal to the thread of the calling process with
+/// the given kernel thread id and returns `true` on success.
+///
+/// Unlike `pthread_kill`, this is safe to call even if the thread has already
+/// exited.
+#[inline]
+pub fn send(tid: pid_t) -> bool {
+    unsafe {
+        let pid = libc::getpid();
+        libc::syscall(libc::SYS_tgkill, pid, tid, neutralization_signal()) == 0
+    }
+}
+
+/// The signal handler, which neutralizes the participant of the interrupted
+/// thread, if it is currently in a read phase.
+///
+/// Only async-signal-safe operations are allowed here, the thread local
+/// storage is constant-initialized and has no destructor.
+extern "C" fn handle_neutralization(_: c_int) {
+    let _ = CURRENT.try_with(|current| {
+        if let Some(participant) = unsafe { current.get().as_ref() } {
+            participant.neutralize();
+        }
+    });
+}
@@ -1,8 +1,5 @@
 //! Inherent implementation and trait implementations for the [`Owned`] type.
 
-#[cfg(not(feature = "std"))]
-use alloc::boxed::Box;
-
 use core::borrow::{Borrow, BorrowMut};
 use core::fmt;
 use core::marker::PhantomData;
@@ -12,9 +9,10 @@ use core::ptr::NonNull;
 
 use typenum::Unsigned;
 
+use crate::allocator::{Allocation, Allocator, Global};
 use crate::internal::Internal;
 use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer};
-use crate::{Owned, Reclaim, Record, Shared, Unprotected};
+use crate::{Owned, Pointee, Reclaim, Record, Shared, Unprotected};
 
 /********** impl Clone ****************************************************************************/
 
@@ -28,12 +26,12 @@ impl<T: Clone, R: Reclaim, N: Unsigned> Clone for Owned<T, R, N> {
 
 /********** impl Send + Sync **********************************************************************/
 
-unsafe impl<T, R: Reclaim, N: Unsigned> Send for Owned<T, R, N> where T: Send {}
-unsafe impl<T, R: Reclaim, N: Unsigned> Sync for Owned<T, R, N> where T: Sync {}
+unsafe impl<T, R: Reclaim, N: Unsigned> Send for Owned<T, R, N> where T: ?Sized + Pointee + Send {}
+unsafe impl<T, R: Reclaim, N: Unsigned> Sync for Owned<T, R, N> where T: ?Sized + Pointee + Sync {}
 
 /********** impl MarkedPointer ********************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> MarkedPointer for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> MarkedPointer for Owned<T, R, N> {
     impl_trait!(owned);
 }
 
@@ -43,15 +41,35 @@ impl<T, R: Reclaim, N: Unsigned> Owned<T, R, N> {
     /// Allocates memory for a [`Record<T>`](Record) on the heap and then
     /// places a record with a default header and `owned` into it.
     ///
-    /// This does only allocate memory if at least one of
-    /// [`RecordHeader`][header] or `T` are not zero-sized.
-    /// If the [`RecordHeader`][header] is a ZST, this behaves
-    /// identically to `Box::new`.
+    /// Besides the record itself, the allocated memory also stores a pointer
+    /// to the function for de-allocating it again.
     ///
     /// [header]: crate::LocalReclaim::RecordHeader
     #[inline]
     pub fn new(owned: T) -> Self {
-        Self { inner: MarkedNonNull::from(Self::alloc_record(owned)), _marker: PhantomData }
+        Self::new_in(owned, Global)
+    }
+
+    /// Allocates memory for a [`Record<T>`](Record) with the given `alloc`
+    /// and then places a record with a default header and `owned` into it.
+    ///
+    /// The allocator is stored alongside the record and is used for
+    /// de-allocating it, when the [`Owned`] is dropped or the record is
+    /// reclaimed after being retired.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reclaim::allocator::Global;
+    ///
+    /// type Owned<T> = reclaim::leak::Owned<T, reclaim::typenum::U0>;
+    ///
+    /// let owned = Owned::new_in(1, Global);
+    /// assert_eq!(*owned, 1);
+    /// ```
+    #[inline]
+    pub fn new_in<A: Allocator + Send + 'static>(owned: T, alloc: A) -> Self {
+        Self { inner: MarkedNonNull::from(Self::alloc_record(owned, alloc)), _marker: PhantomData }
     }
 
     /// Creates a new `Owned` like [`new`](Owned::new) but composes the
@@ -80,7 +98,8 @@ impl<T, R: Reclaim, N: Unsigned> Owned<T, R, N> {
     /// ```
     #[inline]
     pub fn with_tag(owned: T, tag: usize) -> Self {
-        Self { inner: MarkedNonNull::compose(Self::alloc_record(owned), tag), _marker: PhantomData }
+        let inner = MarkedNonNull::compose(Self::alloc_record(owned, Global), tag);
+        Self { inner, _marker: PhantomData }
     }
 
     /// Consumes the [`Owned`], de-allocates its memory and extracts the
@@ -92,11 +111,20 @@ impl<T, R: Reclaim, N: Unsigned> Owned<T, R, N> {
         unsafe {
             let ptr = self.inner.decompose_ptr();
             mem::forget(self);
-            let boxed = Box::from_raw(Record::<_, R>::from_raw(ptr).as_ptr());
-            (*boxed).elem
+            Allocation::into_elem(Record::<_, R>::from_raw(ptr))
         }
     }
 
+    /// Allocates a records wrapping `owned` with the given `alloc` and
+    /// returns the pointer to the wrapped value.
+    #[inline]
+    fn alloc_record(owned: T, alloc: impl Allocator + Send + 'static) -> NonNull<T> {
+        let record = Allocation::alloc(Record::<_, R>::new(owned), alloc);
+        unsafe { NonNull::from(&(*record.as_ptr()).elem) }
+    }
+}
+
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Owned<T, R, N> {
     impl_inherent!(owned);
 
     /// Decomposes the internal marked pointer, returning a reference and the
@@ -142,9 +170,9 @@ impl<T, R: Reclaim, N: Unsigned> Owned<T, R, N> {
     where
         T: 'a,
     {
-        let (ptr, tag) = owned.inner.decompose();
+        let mut inner = owned.inner;
         mem::forget(owned);
-        unsafe { (&mut *ptr.as_ptr(), tag) }
+        unsafe { inner.decompose_mut_unbounded() }
     }
 
     /// Leaks the `owned` value and turns it into an [`Unprotected`] value,
@@ -235,26 +263,18 @@ impl<T, R: Reclaim, N: Unsigned> Owned<T, R, N> {
         mem::forget(owned);
         Shared { inner, _marker: PhantomData }
     }
-
-    /// Allocates a records wrapping `owned` and returns the pointer to the
-    /// wrapped value.
-    #[inline]
-    fn alloc_record(owned: T) -> NonNull<T> {
-        let record = Box::leak(Box::new(Record::<_, R>::new(owned)));
-        NonNull::from(&record.elem)
-    }
 }
 
 /********** impl AsRef + AsMut ********************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> AsRef<T> for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> AsRef<T> for Owned<T, R, N> {
     #[inline]
     fn as_ref(&self) -> &T {
         &**self
     }
 }
 
-impl<T, R: Reclaim, N: Unsigned> AsMut<T> for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> AsMut<T> for Owned<T, R, N> {
     #[inline]
     fn as_mut(&mut self) -> &mut T {
         &mut **self
@@ -263,14 +283,14 @@ impl<T, R: Reclaim, N: Unsigned> AsMut<T> for Owned<T, R, N> {
 
 /********** impl Borrow + BorrowMut ***************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Borrow<T> for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Borrow<T> for Owned<T, R, N> {
     #[inline]
     fn borrow(&self) -> &T {
         &**self
     }
 }
 
-impl<T, R: Reclaim, N: Unsigned> BorrowMut<T> for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> BorrowMut<T> for Owned<T, R, N> {
     #[inline]
     fn borrow_mut(&mut self) -> &mut T {
         &mut **self
@@ -288,7 +308,7 @@ impl<T: Default, R: Reclaim, N: Unsigned> Default for Owned<T, R, N> {
 
 /********** impl Deref + DerefMut *****************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Deref for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Deref for Owned<T, R, N> {
     type Target = T;
 
     #[inline]
@@ -297,7 +317,7 @@ impl<T, R: Reclaim, N: Unsigned> Deref for Owned<T, R, N> {
     }
 }
 
-impl<T, R: Reclaim, N: Unsigned> DerefMut for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> DerefMut for Owned<T, R, N> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe { self.inner.as_mut() }
@@ -306,12 +326,11 @@ impl<T, R: Reclaim, N: Unsigned> DerefMut for Owned<T, R, N> {
 
 /********** impl Drop *****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Drop for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Drop for Owned<T, R, N> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            let record = Record::<_, R>::from_raw(self.inner.decompose_ptr());
-            mem::drop(Box::from_raw(record.as_ptr()));
+            Allocation::free(Record::<_, R>::from_raw(self.inner.decompose_ptr()));
         }
     }
 }
@@ -327,20 +346,20 @@ impl<T, R: Reclaim, N: Unsigned> From<T> for Owned<T, R, N> {
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Owned<T, R, N>
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Debug for Owned<T, R, N>
 where
     T: fmt::Debug,
 {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (reference, tag) = unsafe { self.inner.decompose_ref() };
-        f.debug_struct("Owned").field("value", reference).field("tag", &tag).finish()
+        f.debug_struct("Owned").field("value", &reference).field("tag", &tag).finish()
     }
 }
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Pointer for Owned<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.inner.decompose_ptr(), f)
@@ -349,7 +368,7 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Owned<T, R, N> {
 
 /********** impl NonNullable **********************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> MarkedNonNullable for Owned<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> MarkedNonNullable for Owned<T, R, N> {
     type Item = T;
     type MarkBits = N;
 
@@ -363,7 +382,7 @@ impl<T, R: Reclaim, N: Unsigned> MarkedNonNullable for Owned<T, R, N> {
 
 /********** impl Internal *************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Internal for Owned<T, R, N> {}
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Internal for Owned<T, R, N> {}
 
 #[cfg(test)]
 mod test {
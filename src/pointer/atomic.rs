@@ -4,16 +4,17 @@ use core::sync::atomic::{AtomicUsize, Ordering};
 
 use typenum::Unsigned;
 
+use crate::dst::Pointee;
 use crate::pointer::{self, AtomicMarkedPtr, MarkedPtr};
 
 /********** impl Send + Sync **********************************************************************/
 
-unsafe impl<T, N> Send for AtomicMarkedPtr<T, N> {}
-unsafe impl<T, N> Sync for AtomicMarkedPtr<T, N> {}
+unsafe impl<T: ?Sized + Pointee, N> Send for AtomicMarkedPtr<T, N> {}
+unsafe impl<T: ?Sized + Pointee, N> Sync for AtomicMarkedPtr<T, N> {}
 
 /********** impl inherent *************************************************************************/
 
-impl<T, N> AtomicMarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N> AtomicMarkedPtr<T, N> {
     /// Creates a new & unmarked `null` pointer.
     #[inline]
     pub const fn null() -> Self {
@@ -23,11 +24,11 @@ impl<T, N> AtomicMarkedPtr<T, N> {
 
 /********** impl inherent *************************************************************************/
 
-impl<T, N: Unsigned> AtomicMarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> AtomicMarkedPtr<T, N> {
     /// The number of available mark bits for this type.
     pub const MARK_BITS: usize = N::USIZE;
     /// The bitmask for the lower markable bits.
-    pub const MARK_MASK: usize = pointer::mark_mask::<T>(Self::MARK_BITS);
+    pub const MARK_MASK: usize = pointer::mark_mask::<T::Elem>(Self::MARK_BITS);
     /// The bitmask for the (higher) pointer bits.
     pub const POINTER_MASK: usize = !Self::MARK_MASK;
 
@@ -263,7 +264,7 @@ impl<T, N: Unsigned> AtomicMarkedPtr<T, N> {
 
 /********** impl Default **************************************************************************/
 
-impl<T, N: Unsigned> Default for AtomicMarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> Default for AtomicMarkedPtr<T, N> {
     #[inline]
     fn default() -> Self {
         Self::null()
@@ -272,7 +273,7 @@ impl<T, N: Unsigned> Default for AtomicMarkedPtr<T, N> {
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, N: Unsigned> fmt::Debug for AtomicMarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> fmt::Debug for AtomicMarkedPtr<T, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.load(Ordering::SeqCst).decompose();
@@ -282,7 +283,7 @@ impl<T, N: Unsigned> fmt::Debug for AtomicMarkedPtr<T, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, N: Unsigned> fmt::Pointer for AtomicMarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> fmt::Pointer for AtomicMarkedPtr<T, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.load(Ordering::SeqCst).decompose_ptr(), f)
@@ -305,7 +306,7 @@ impl<T, N: Unsigned> From<*mut T> for AtomicMarkedPtr<T, N> {
     }
 }
 
-impl<T, N: Unsigned> From<MarkedPtr<T, N>> for AtomicMarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> From<MarkedPtr<T, N>> for AtomicMarkedPtr<T, N> {
     #[inline]
     fn from(ptr: MarkedPtr<T, N>) -> Self {
         AtomicMarkedPtr::new(ptr)
@@ -1,7 +1,10 @@
 mod atomic;
 mod marked;
 mod non_null;
+mod pair;
 mod raw;
+#[cfg(target_pointer_width = "64")]
+mod stamped;
 
 #[cfg(feature = "std")]
 use std::error::Error;
@@ -9,11 +12,13 @@ use std::error::Error;
 use core::fmt;
 use core::marker::PhantomData;
 use core::mem;
-use core::ptr::{self, NonNull};
+use core::cell::UnsafeCell;
+use core::ptr::NonNull;
 use core::sync::atomic::AtomicUsize;
 
 use typenum::Unsigned;
 
+use crate::dst::Pointee;
 use crate::internal::Internal;
 
 use self::Marked::{Null, Value};
@@ -27,7 +32,7 @@ pub trait MarkedPointer: Sized + Internal {
     /// The pointer type.
     type Pointer: MarkedNonNullable<Item = Self::Item, MarkBits = Self::MarkBits>;
     /// The pointed-to type.
-    type Item: Sized;
+    type Item: ?Sized + Pointee;
     /// Number of bits available for tagging.
     type MarkBits: Unsigned;
 
@@ -96,8 +101,12 @@ pub trait MarkedPointer: Sized + Internal {
 /// have up to `3` mark bits.
 /// Attempts to use types with insufficient alignment will result in a compile-
 /// time error.
-pub struct MarkedPtr<T, N> {
-    inner: *mut T,
+///
+/// For dynamically sized types `T`, the pointer is a thin pointer to the
+/// record element ([`Pointee::Elem`]) storing the value's wide pointer, so
+/// the alignment of the element determines the available mark bits.
+pub struct MarkedPtr<T: ?Sized + Pointee, N> {
+    inner: *mut T::Elem,
     _marker: PhantomData<N>,
 }
 
@@ -109,8 +118,8 @@ pub struct MarkedPtr<T, N> {
 ///
 /// Note, that unlike [`MarkedPtr`][MarkedPtr] this also **excludes** marked
 /// null-pointers.
-pub struct MarkedNonNull<T, N> {
-    inner: NonNull<T>,
+pub struct MarkedNonNull<T: ?Sized + Pointee, N> {
+    inner: NonNull<T::Elem>,
     _marker: PhantomData<N>,
 }
 
@@ -127,7 +136,86 @@ pub struct MarkedNonNull<T, N> {
 ///
 /// [atomic]: std::sync::atomic::AtomicPtr
 /// [marked]: MarkedPtr
-pub struct AtomicMarkedPtr<T, N> {
+pub struct AtomicMarkedPtr<T: ?Sized + Pointee, N> {
+    inner: AtomicUsize,
+    _marker: PhantomData<(*mut T, N)>,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// AtomicMarkedPair
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A pair of a [`MarkedPtr`] and a 64-bit counter, which can be atomically
+/// loaded, swapped and compared and exchanged as a whole.
+///
+/// All operations are sequentially consistent.
+///
+/// On `x86_64`, all operations are implemented with the double-width
+/// `cmpxchg16b` instruction, if the CPU supports it.
+/// On all other platforms and CPUs, the operations fall back to using a global
+/// lock, so this type should only be used for algorithms that actually require
+/// updating both values at once.
+#[repr(C, align(16))]
+pub struct AtomicMarkedPair<T, N> {
+    inner: UnsafeCell<u128>,
+    _marker: PhantomData<(*mut T, N)>,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// PairExchangeFailure
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The returned error type for a failed
+/// [`compare_exchange`][AtomicMarkedPair::compare_exchange] operation on an
+/// [`AtomicMarkedPair`].
+pub struct PairExchangeFailure<T, N, S> {
+    /// The actually loaded pointer and counter.
+    pub loaded: (MarkedPtr<T, N>, u64),
+    /// The pointer with which the failed exchange was attempted.
+    pub input: S,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RawPairExchangeResult
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Result type for [`compare_exchange_raw`][AtomicMarkedPair::compare_exchange_raw]
+/// operations.
+pub type RawPairExchangeResult<T, N> = Result<(MarkedPtr<T, N>, u64), (MarkedPtr<T, N>, u64)>;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// StampedPtr
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A [`MarkedPtr`] combined with a version *stamp*, which is stored in the
+/// upper (unused) 16 bits of the pointer's address.
+///
+/// On 64-bit platforms such as `x86_64` and `aarch64`, user-space addresses
+/// only use the lower 48 bits, so the remaining bits are free for storing
+/// additional information that does not depend on the alignment of `T`.
+#[cfg(target_pointer_width = "64")]
+pub struct StampedPtr<T, N> {
+    inner: usize,
+    _marker: PhantomData<(*mut T, N)>,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// AtomicStampedPtr
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A raw atomic pointer type like [`AtomicMarkedPtr`], which additionally
+/// stores a wrapping version counter (the *stamp*) in the upper (unused) bits
+/// of the pointer's address.
+///
+/// The stamp is automatically incremented by every operation that writes to
+/// the pointer, which prevents the *ABA* problem for most practical purposes,
+/// since a pointer would have to be re-written exactly `2^16` times between a
+/// load and a subsequent *compare-and-swap* to go unnoticed.
+///
+/// Whether the platform's address width leaves enough bits unused can be
+/// checked with [`is_supported`][AtomicStampedPtr::is_supported].
+#[cfg(target_pointer_width = "64")]
+pub struct AtomicStampedPtr<T, N> {
     inner: AtomicUsize,
     _marker: PhantomData<(*mut T, N)>,
 }
@@ -151,7 +239,7 @@ pub enum Marked<T: MarkedNonNullable> {
 
 /********** blanket impls *************************************************************************/
 
-impl<U, T, N: Unsigned> MarkedPointer for Option<U>
+impl<U, T: ?Sized + Pointee, N: Unsigned> MarkedPointer for Option<U>
 where
     U: MarkedPointer<Pointer = U, Item = T, MarkBits = N>
         + MarkedNonNullable<Item = T, MarkBits = N>,
@@ -215,7 +303,7 @@ where
     }
 }
 
-impl<U, T, N: Unsigned> MarkedPointer for Marked<U>
+impl<U, T: ?Sized + Pointee, N: Unsigned> MarkedPointer for Marked<U>
 where
     U: MarkedPointer<Pointer = U, Item = T, MarkBits = N>
         + MarkedNonNullable<Item = T, MarkBits = N>,
@@ -228,7 +316,7 @@ where
     fn as_marked_ptr(&self) -> MarkedPtr<Self::Item, Self::MarkBits> {
         match self {
             Value(ptr) => Self::Pointer::as_marked_ptr(ptr),
-            Null(tag) => MarkedPtr::compose(ptr::null_mut(), *tag),
+            Null(tag) => MarkedPtr::null().with_tag(*tag),
         }
     }
 
@@ -236,7 +324,7 @@ where
     fn into_marked_ptr(self) -> MarkedPtr<Self::Item, Self::MarkBits> {
         match self {
             Value(ptr) => Self::Pointer::into_marked_ptr(ptr),
-            Null(tag) => MarkedPtr::compose(ptr::null_mut(), tag),
+            Null(tag) => MarkedPtr::null().with_tag(tag),
         }
     }
 
@@ -308,7 +396,7 @@ impl Error for InvalidNullError {}
 /// A sealed (internal) trait for non-nullable marked pointer types.
 pub trait MarkedNonNullable: Sized + Internal {
     /// The pointed-to type.
-    type Item: Sized;
+    type Item: ?Sized + Pointee;
     /// Number of bits available for tagging.
     type MarkBits: Unsigned;
 
@@ -359,12 +447,12 @@ impl<T> MarkedNonNullable for NonNull<T> {
 
 /********** impl Internal *************************************************************************/
 
-impl<U, T, N: Unsigned> Internal for Option<U> where
+impl<U, T: ?Sized + Pointee, N: Unsigned> Internal for Option<U> where
     U: MarkedPointer<Item = T, MarkBits = N> + MarkedNonNullable<Item = T, MarkBits = N>
 {
 }
 
-impl<U, T, N: Unsigned> Internal for Marked<U> where
+impl<U, T: ?Sized + Pointee, N: Unsigned> Internal for Marked<U> where
     U: MarkedPointer<Item = T, MarkBits = N> + MarkedNonNullable<Item = T, MarkBits = N>
 {
 }
@@ -6,6 +6,7 @@ use core::ptr::NonNull;
 
 use typenum::{IsGreaterOrEqual, True, Unsigned};
 
+use crate::dst::Pointee;
 use crate::internal::Internal;
 use crate::pointer::{
     self, InvalidNullError,
@@ -15,23 +16,23 @@ use crate::pointer::{
 
 /********** impl Clone ****************************************************************************/
 
-impl<T, N> Clone for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> Clone for MarkedNonNull<T, N> {
     #[inline]
     fn clone(&self) -> Self {
-        Self::from(self.inner)
+        Self { inner: self.inner, _marker: PhantomData }
     }
 }
 
 /********** impl Copy *****************************************************************************/
 
-impl<T, N> Copy for MarkedNonNull<T, N> {}
+impl<T: ?Sized + Pointee, N> Copy for MarkedNonNull<T, N> {}
 
 /********** impl inherent *************************************************************************/
 
-impl<T, N> MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> MarkedNonNull<T, N> {
     /// Cast to a pointer of another type.
     #[inline]
-    pub const fn cast<U>(self) -> MarkedNonNull<U, N> {
+    pub const fn cast<U: ?Sized + Pointee>(self) -> MarkedNonNull<U, N> {
         MarkedNonNull { inner: self.inner.cast(), _marker: PhantomData }
     }
 
@@ -50,18 +51,18 @@ impl<T, N> MarkedNonNull<T, N> {
     }
 }
 
-impl<T, N: Unsigned> MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> MarkedNonNull<T, N> {
     /// The number of available mark bits for this type.
     pub const MARK_BITS: usize = N::USIZE;
     /// The bitmask for the lower markable bits.
-    pub const MARK_MASK: usize = pointer::mark_mask::<T>(Self::MARK_BITS);
+    pub const MARK_MASK: usize = pointer::mark_mask::<T::Elem>(Self::MARK_BITS);
     /// The bitmask for the (higher) pointer bits.
     pub const POINTER_MASK: usize = !Self::MARK_MASK;
 
     /// Returns the inner pointer *as is*, meaning potential tags are not
     /// stripped.
     #[inline]
-    pub fn into_non_null(self) -> NonNull<T> {
+    pub fn into_non_null(self) -> NonNull<T::Elem> {
         self.inner
     }
 
@@ -73,7 +74,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     where
         N: IsGreaterOrEqual<M, Output = True>,
     {
-        Self::from(other.inner)
+        Self { inner: other.inner, _marker: PhantomData }
     }
 
     /// Creates a new `MarkedNonNull` from a marked pointer without checking
@@ -85,7 +86,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// null pointer.
     #[inline]
     pub unsafe fn new_unchecked(ptr: MarkedPtr<T, N>) -> Self {
-        Self::from(NonNull::new_unchecked(ptr.inner))
+        Self { inner: NonNull::new_unchecked(ptr.inner), _marker: PhantomData }
     }
 
     /// Creates a new `MarkedNonNull` wrapped in a [`Marked`] if `ptr` is
@@ -100,47 +101,40 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// Clears the tag of `self` and returns the same but untagged pointer.
     #[inline]
     pub fn clear_tag(self) -> Self {
-        Self::from(self.decompose_non_null())
+        Self { inner: self.decompose_non_null(), _marker: PhantomData }
     }
 
     /// Clears the tag of `self` and replaces it with `tag`.
     #[inline]
     pub fn with_tag(self, tag: usize) -> Self {
-        Self::compose(self.decompose_non_null(), tag)
+        let inner = pointer::compose::<_, N>(self.decompose_ptr(), tag);
+        Self { inner: unsafe { NonNull::new_unchecked(inner) }, _marker: PhantomData }
     }
 
     /// Converts the pointer to the equivalent [`MarkedPtr`].
     #[inline]
     pub fn into_marked_ptr(self) -> MarkedPtr<T, N> {
-        MarkedPtr::new(self.inner.as_ptr())
-    }
-
-    /// Composes a new marked non-null pointer from a non-null pointer and a tag
-    /// value.
-    #[inline]
-    pub fn compose(ptr: NonNull<T>, tag: usize) -> Self {
-        debug_assert_eq!(0, ptr.as_ptr() as usize & Self::MARK_MASK, "`ptr` is not well aligned");
-        unsafe { Self::from(NonNull::new_unchecked(pointer::compose::<_, N>(ptr.as_ptr(), tag))) }
+        MarkedPtr { inner: self.inner.as_ptr(), _marker: PhantomData }
     }
 
     /// Decomposes the marked pointer, returning the separated raw
     /// [`NonNull`] pointer and its tag.
     #[inline]
-    pub fn decompose(self) -> (NonNull<T>, usize) {
+    pub fn decompose(self) -> (NonNull<T::Elem>, usize) {
         let (ptr, tag) = pointer::decompose(self.inner.as_ptr() as usize, Self::MARK_BITS);
         (unsafe { NonNull::new_unchecked(ptr) }, tag)
     }
 
     /// Decomposes the marked pointer, returning only the separated raw pointer.
     #[inline]
-    pub fn decompose_ptr(self) -> *mut T {
+    pub fn decompose_ptr(self) -> *mut T::Elem {
         pointer::decompose_ptr(self.inner.as_ptr() as usize, Self::MARK_BITS)
     }
 
     /// Decomposes the marked pointer, returning only the separated raw
     /// [`NonNull`] pointer.
     #[inline]
-    pub fn decompose_non_null(self) -> NonNull<T> {
+    pub fn decompose_non_null(self) -> NonNull<T::Elem> {
         unsafe {
             NonNull::new_unchecked(pointer::decompose_ptr(
                 self.inner.as_ptr() as usize,
@@ -152,7 +146,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// Decomposes the marked pointer, returning only the separated tag.
     #[inline]
     pub fn decompose_tag(self) -> usize {
-        pointer::decompose_tag::<T>(self.inner.as_ptr() as usize, Self::MARK_BITS)
+        pointer::decompose_tag::<T::Elem>(self.inner.as_ptr() as usize, Self::MARK_BITS)
     }
 
     /// Decomposes the marked pointer, dereferences the the raw pointer and
@@ -169,7 +163,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     #[inline]
     pub unsafe fn decompose_ref(&self) -> (&T, usize) {
         let (ptr, tag) = self.decompose();
-        (&*ptr.as_ptr(), tag)
+        (&*T::from_elem(ptr).as_ptr(), tag)
     }
 
     /// Decomposes the marked pointer, dereferences the the raw pointer and
@@ -184,7 +178,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     #[inline]
     pub unsafe fn decompose_ref_unbounded<'a>(self) -> (&'a T, usize) {
         let (ptr, tag) = self.decompose();
-        (&*ptr.as_ptr(), tag)
+        (&*T::from_elem(ptr).as_ptr(), tag)
     }
 
     /// Decomposes the marked pointer, mutably dereferences the the raw pointer
@@ -201,7 +195,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     #[inline]
     pub unsafe fn decompose_mut(&mut self) -> (&mut T, usize) {
         let (ptr, tag) = self.decompose();
-        (&mut *ptr.as_ptr(), tag)
+        (&mut *T::from_elem(ptr).as_ptr(), tag)
     }
 
     /// Decomposes the marked pointer, mutably dereferences the the raw pointer
@@ -216,7 +210,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     #[inline]
     pub unsafe fn decompose_mut_unbounded<'a>(&mut self) -> (&'a mut T, usize) {
         let (ptr, tag) = self.decompose();
-        (&mut *ptr.as_ptr(), tag)
+        (&mut *T::from_elem(ptr).as_ptr(), tag)
     }
 
     /// Decomposes the marked pointer, returning only the de-referenced raw
@@ -233,7 +227,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// pointer.
     #[inline]
     pub unsafe fn as_ref(&self) -> &T {
-        &*self.decompose_non_null().as_ptr()
+        &*T::from_elem(self.decompose_non_null()).as_ptr()
     }
 
     /// Decomposes the marked pointer, returning only the de-referenced raw
@@ -246,7 +240,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// valid lifetime for the contained data.
     #[inline]
     pub unsafe fn as_ref_unbounded<'a>(self) -> &'a T {
-        &*self.decompose_non_null().as_ptr()
+        &*T::from_elem(self.decompose_non_null()).as_ptr()
     }
 
     /// Decomposes the marked pointer, returning only the mutably de-referenced
@@ -263,7 +257,7 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// pointer.
     #[inline]
     pub unsafe fn as_mut(&mut self) -> &mut T {
-        &mut *self.decompose_non_null().as_ptr()
+        &mut *T::from_elem(self.decompose_non_null()).as_ptr()
     }
 
     /// Decomposes the marked pointer, returning only the mutably de-referenced
@@ -276,13 +270,25 @@ impl<T, N: Unsigned> MarkedNonNull<T, N> {
     /// valid lifetime for the contained data.
     #[inline]
     pub unsafe fn as_mut_unbounded<'a>(self) -> &'a mut T {
-        &mut *self.decompose_non_null().as_ptr()
+        &mut *T::from_elem(self.decompose_non_null()).as_ptr()
+    }
+}
+
+/********** impl inherent (sized) *****************************************************************/
+
+impl<T, N: Unsigned> MarkedNonNull<T, N> {
+    /// Composes a new marked non-null pointer from a non-null pointer and a tag
+    /// value.
+    #[inline]
+    pub fn compose(ptr: NonNull<T>, tag: usize) -> Self {
+        debug_assert_eq!(0, ptr.as_ptr() as usize & Self::MARK_MASK, "`ptr` is not well aligned");
+        unsafe { Self::from(NonNull::new_unchecked(pointer::compose::<_, N>(ptr.as_ptr(), tag))) }
     }
 }
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, N: Unsigned> fmt::Debug for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> fmt::Debug for MarkedNonNull<T, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.decompose();
@@ -292,7 +298,7 @@ impl<T, N: Unsigned> fmt::Debug for MarkedNonNull<T, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, N: Unsigned> fmt::Pointer for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> fmt::Pointer for MarkedNonNull<T, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.decompose_non_null(), f)
@@ -324,7 +330,7 @@ impl<'a, T, N: Unsigned> From<&'a mut T> for MarkedNonNull<T, N> {
 
 /********** impl TryFrom **************************************************************************/
 
-impl<T, N: Unsigned> TryFrom<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> TryFrom<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
     type Error = InvalidNullError;
 
     #[inline]
@@ -338,14 +344,14 @@ impl<T, N: Unsigned> TryFrom<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
 
 /********** impl PartialEq ************************************************************************/
 
-impl<T, N> PartialEq for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> PartialEq for MarkedNonNull<T, N> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.inner == other.inner
     }
 }
 
-impl<T, N> PartialEq<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> PartialEq<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
     #[inline]
     fn eq(&self, other: &MarkedPtr<T, N>) -> bool {
         self.inner.as_ptr() == other.inner
@@ -354,14 +360,14 @@ impl<T, N> PartialEq<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
 
 /********** impl PartialOrd ***********************************************************************/
 
-impl<T, N> PartialOrd for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> PartialOrd for MarkedNonNull<T, N> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         self.inner.partial_cmp(&other.inner)
     }
 }
 
-impl<T, N> PartialOrd<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> PartialOrd<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
     #[inline]
     fn partial_cmp(&self, other: &MarkedPtr<T, N>) -> Option<cmp::Ordering> {
         self.inner.as_ptr().partial_cmp(&other.inner)
@@ -370,11 +376,11 @@ impl<T, N> PartialOrd<MarkedPtr<T, N>> for MarkedNonNull<T, N> {
 
 /********** impl Eq *******************************************************************************/
 
-impl<T, N> Eq for MarkedNonNull<T, N> {}
+impl<T: ?Sized + Pointee, N> Eq for MarkedNonNull<T, N> {}
 
 /********** impl Ord ******************************************************************************/
 
-impl<T, N> Ord for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N> Ord for MarkedNonNull<T, N> {
     #[inline]
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.inner.cmp(&other.inner)
@@ -383,7 +389,7 @@ impl<T, N> Ord for MarkedNonNull<T, N> {
 
 /********** impl NonNullable **********************************************************************/
 
-impl<T, N: Unsigned> MarkedNonNullable for MarkedNonNull<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> MarkedNonNullable for MarkedNonNull<T, N> {
     type Item = T;
     type MarkBits = N;
 
@@ -395,7 +401,7 @@ impl<T, N: Unsigned> MarkedNonNullable for MarkedNonNull<T, N> {
 
 /********** impl Internal *************************************************************************/
 
-impl<T, N: Unsigned> Internal for MarkedNonNull<T, N> {}
+impl<T: ?Sized + Pointee, N: Unsigned> Internal for MarkedNonNull<T, N> {}
 
 #[cfg(test)]
 mod tests {
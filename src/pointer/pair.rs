@@ -0,0 +1,369 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+
+use typenum::Unsigned;
+
+use crate::internal::{Compare, Store};
+use crate::pointer::{
+    AtomicMarkedPair, MarkedPointer, MarkedPtr, PairExchangeFailure, RawPairExchangeResult,
+};
+use crate::{Reclaim, Unlinked, Unprotected};
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<T, N> Send for AtomicMarkedPair<T, N> {}
+unsafe impl<T, N> Sync for AtomicMarkedPair<T, N> {}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, N> AtomicMarkedPair<T, N> {
+    /// Creates a new & unmarked `null` pointer with a counter of zero.
+    #[inline]
+    pub const fn null() -> Self {
+        Self { inner: UnsafeCell::new(0), _marker: PhantomData }
+    }
+}
+
+impl<T, N: Unsigned> AtomicMarkedPair<T, N> {
+    /// Creates a new `AtomicMarkedPair` from a pointer and a counter.
+    #[inline]
+    pub fn new(ptr: MarkedPtr<T, N>, count: u64) -> Self {
+        Self { inner: UnsafeCell::new(pack(ptr, count)), _marker: PhantomData }
+    }
+
+    /// Consumes `self` and returns the inner pointer and counter.
+    #[inline]
+    pub fn into_inner(self) -> (MarkedPtr<T, N>, u64) {
+        unpack(self.inner.into_inner())
+    }
+
+    /// Loads the raw pointer and the counter.
+    #[inline]
+    pub fn load_raw(&self) -> (MarkedPtr<T, N>, u64) {
+        // an exchange of a value with itself is an atomic load
+        match unsafe { dwcas::compare_exchange(self.inner.get(), 0, 0) } {
+            Ok(pair) | Err(pair) => unpack(pair),
+        }
+    }
+
+    /// Loads an optional [`Unprotected`] reference and the counter.
+    ///
+    /// The returned reference is explicitly **not** protected from reclamation,
+    /// just like with [`Atomic::load_unprotected`][crate::Atomic::load_unprotected].
+    /// Since an `AtomicMarkedPair` is not bound to any specific reclamation
+    /// scheme, the reclaimer `R` has to be specified by the caller.
+    #[inline]
+    pub fn load_unprotected<R: Reclaim>(&self) -> (Option<Unprotected<T, R, N>>, u64) {
+        let (ptr, count) = self.load_raw();
+        (unsafe { Option::from_marked_ptr(ptr) }, count)
+    }
+
+    /// Stores the pointer `new` (either null or valid) and the counter `count`
+    /// and returns the previous and now unlinked value along with the previous
+    /// counter, just like with [`Atomic::swap`][crate::Atomic::swap].
+    #[inline]
+    pub fn swap<S>(&self, new: S, count: u64) -> (Option<Unlinked<T, S::Reclaimer, N>>, u64)
+    where
+        S: Store<Item = T, MarkBits = N>,
+    {
+        let new = pack(MarkedPointer::into_marked_ptr(new), count);
+        let mut current = 0;
+        loop {
+            match unsafe { dwcas::compare_exchange(self.inner.get(), current, new) } {
+                Ok(prev) => {
+                    let (ptr, count) = unpack(prev);
+                    // this is safe because the pointer is no longer accessible by other threads
+                    return (unsafe { Option::from_marked_ptr(ptr) }, count);
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Stores the pointer `new` and its counter if the current pointer and
+    /// counter are the same as `current`.
+    ///
+    /// On success, the previous and now unlinked value is returned along with
+    /// the previous counter, just like with
+    /// [`Atomic::compare_exchange`][crate::Atomic::compare_exchange].
+    /// On failure, a [struct](PairExchangeFailure) containing both the actually
+    /// loaded pointer and counter and the value that was attempted to be
+    /// inserted is returned.
+    #[inline]
+    pub fn compare_exchange<C, S>(
+        &self,
+        current: (C, u64),
+        new: (S, u64),
+    ) -> Result<(C::Unlinked, u64), PairExchangeFailure<T, N, S>>
+    where
+        C: Compare<Item = T, MarkBits = N>,
+        S: Store<Item = T, MarkBits = N, Reclaimer = C::Reclaimer>,
+    {
+        let (current, current_count) = current;
+        let (new, new_count) = new;
+        let current = MarkedPointer::into_marked_ptr(current);
+        let new = MarkedPointer::into_marked_ptr(new);
+
+        self.compare_exchange_raw((current, current_count), (new, new_count))
+            .map(|(ptr, count)| (unsafe { C::Unlinked::from_marked_ptr(ptr) }, count))
+            .map_err(|loaded| PairExchangeFailure {
+                loaded,
+                input: unsafe { S::from_marked_ptr(new) },
+            })
+    }
+
+    /// Stores the raw pointer and counter `new` if the current pointer and
+    /// counter are the same as `current`.
+    ///
+    /// The return value is a result containing the previous pointer and
+    /// counter, which on success is guaranteed to be equal to `current`.
+    #[inline]
+    pub fn compare_exchange_raw(
+        &self,
+        current: (MarkedPtr<T, N>, u64),
+        new: (MarkedPtr<T, N>, u64),
+    ) -> RawPairExchangeResult<T, N> {
+        let (current, new) = (pack(current.0, current.1), pack(new.0, new.1));
+        unsafe { dwcas::compare_exchange(self.inner.get(), current, new) }
+            .map(unpack)
+            .map_err(unpack)
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T, N> Default for AtomicMarkedPair<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, N: Unsigned> fmt::Debug for AtomicMarkedPair<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (ptr, count) = self.load_raw();
+        let (ptr, tag) = ptr.decompose();
+        f.debug_struct("AtomicMarkedPair")
+            .field("ptr", &ptr)
+            .field("tag", &tag)
+            .field("count", &count)
+            .finish()
+    }
+}
+
+impl<T, N: Unsigned, S: fmt::Debug> fmt::Debug for PairExchangeFailure<T, N, S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PairExchangeFailure")
+            .field("loaded", &self.loaded)
+            .field("input", &self.input)
+            .finish()
+    }
+}
+
+/********** helper functions **********************************************************************/
+
+/// Packs a pointer and a counter into their double-width integer
+/// representation.
+#[inline]
+fn pack<T, N: Unsigned>(ptr: MarkedPtr<T, N>, count: u64) -> u128 {
+    (ptr.into_usize() as u128) | (u128::from(count) << 64)
+}
+
+/// Unpacks the double-width integer representation of a pointer and a
+/// counter.
+#[inline]
+fn unpack<T, N: Unsigned>(pair: u128) -> (MarkedPtr<T, N>, u64) {
+    (MarkedPtr::from_usize(pair as u64 as usize), (pair >> 64) as u64)
+}
+
+mod dwcas {
+    //! Double-width *compare-and-swap* with a lock-based fallback.
+
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    /// The global lock for all double-width operations on platforms without
+    /// native support.
+    static LOCK: AtomicBool = AtomicBool::new(false);
+
+    /// Compares the value at `dst` with `current` and replaces it with `new`,
+    /// if both are equal, returning the previous value.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must be valid for reads and writes, aligned to 16 bytes and only
+    /// ever be accessed through this function.
+    #[inline]
+    pub(super) unsafe fn compare_exchange(
+        dst: *mut u128,
+        current: u128,
+        new: u128,
+    ) -> Result<u128, u128> {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if has_cmpxchg16b() {
+                let prev = cmpxchg16b(dst, current, new);
+                return if prev == current { Ok(prev) } else { Err(prev) };
+            }
+        }
+
+        compare_exchange_locked(dst, current, new)
+    }
+
+    /// Compares and exchanges the value at `dst` while holding the global
+    /// lock, which serializes all operations.
+    #[inline]
+    pub(super) unsafe fn compare_exchange_locked(
+        dst: *mut u128,
+        current: u128,
+        new: u128,
+    ) -> Result<u128, u128> {
+        while LOCK.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        let prev = dst.read();
+        if prev == current {
+            dst.write(new);
+        }
+
+        LOCK.store(false, Ordering::Release);
+        if prev == current {
+            Ok(prev)
+        } else {
+            Err(prev)
+        }
+    }
+
+    /// Returns `true` if the CPU supports the `cmpxchg16b` instruction.
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    fn has_cmpxchg16b() -> bool {
+        #[cfg(feature = "std")]
+        return std::is_x86_feature_detected!("cmpxchg16b");
+        #[cfg(not(feature = "std"))]
+        return cfg!(target_feature = "cmpxchg16b");
+    }
+
+    /// Executes a `lock cmpxchg16b` instruction, which is sequentially
+    /// consistent regardless of the requested orderings.
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    unsafe fn cmpxchg16b(dst: *mut u128, current: u128, new: u128) -> u128 {
+        let (lo, hi): (u64, u64);
+        // `rbx` can not be named as an operand, so it has to be swapped in and out manually and the
+        // other operands are pinned, since `rbx` may otherwise be allocated for any of them
+        core::arch::asm!(
+            "xchg rsi, rbx",
+            "lock cmpxchg16b xmmword ptr [rdi]",
+            "mov rbx, rsi",
+            in("rdi") dst,
+            inout("rsi") new as u64 => _,
+            in("rcx") (new >> 64) as u64,
+            inout("rax") current as u64 => lo,
+            inout("rdx") (current >> 64) as u64 => hi,
+            options(nostack),
+        );
+
+        u128::from(lo) | (u128::from(hi) << 64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use typenum::U2;
+
+    use crate::align::Aligned4;
+    use crate::leak::Leaking;
+    use crate::{MarkedPointer, Owned, Unprotected};
+
+    type AtomicMarkedPair<T> = super::AtomicMarkedPair<T, U2>;
+    type MarkedPtr<T> = crate::pointer::MarkedPtr<T, U2>;
+
+    #[test]
+    fn pack() {
+        let mut value = Aligned4(1);
+        let ptr = MarkedPtr::compose(&mut value, 0b11);
+        assert_eq!(super::unpack(super::pack(ptr, u64::MAX)), (ptr, u64::MAX));
+    }
+
+    #[test]
+    fn swap_compare_exchange() {
+        let pair = AtomicMarkedPair::null();
+        assert_eq!(pair.load_raw(), (MarkedPtr::null(), 0));
+
+        let owned = Owned::<_, Leaking, U2>::new(Aligned4(1));
+        let ptr = owned.as_marked_ptr();
+        let (prev, count) = pair.swap(owned, 1);
+        assert!(prev.is_none());
+        assert_eq!(count, 0);
+        assert_eq!(pair.load_raw(), (ptr, 1));
+
+        let (loaded, count) = pair.load_unprotected::<Leaking>();
+        assert_eq!((loaded.unwrap().as_marked_ptr(), count), (ptr, 1));
+
+        // the pointer matches, but the counter does not
+        let res = pair.compare_exchange_raw((ptr, 0), (MarkedPtr::null(), 2));
+        assert_eq!(res, Err((ptr, 1)));
+        let res = pair.compare_exchange_raw((ptr, 1), (MarkedPtr::null(), 2));
+        assert_eq!(res, Ok((ptr, 1)));
+
+        let none: Option<Unprotected<_, Leaking, U2>> = None;
+        let owned = unsafe { Owned::<_, Leaking, U2>::from_marked_ptr(ptr) };
+        let failure = pair.compare_exchange((none, 0), (owned, 3)).unwrap_err();
+        assert_eq!(failure.loaded, (MarkedPtr::null(), 2));
+        assert!(pair.compare_exchange((none, 2), (failure.input, 3)).is_ok());
+        assert_eq!(pair.load_raw(), (ptr, 3));
+
+        let (unlinked, count) = pair.swap(Option::<Owned<_, Leaking, U2>>::None, 4);
+        assert_eq!((unlinked.unwrap().as_marked_ptr(), count), (ptr, 3));
+    }
+
+    #[test]
+    fn locked() {
+        let mut value = 0u128;
+        let dst = &mut value as *mut u128;
+        unsafe {
+            assert_eq!(super::dwcas::compare_exchange_locked(dst, 1, 2), Err(0));
+            assert_eq!(super::dwcas::compare_exchange_locked(dst, 0, 1 << 64), Ok(0));
+        }
+        assert_eq!(value, 1 << 64);
+    }
+
+    #[test]
+    fn concurrent_increment() {
+        const THREADS: usize = 4;
+        const INCREMENTS: u64 = 1000;
+
+        let pair = Arc::new(AtomicMarkedPair::<Aligned4<i32>>::null());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let pair = Arc::clone(&pair);
+                thread::spawn(move || {
+                    for _ in 0..INCREMENTS {
+                        let mut current = pair.load_raw();
+                        while let Err(actual) =
+                            pair.compare_exchange_raw(current, (current.0, current.1 + 1))
+                        {
+                            current = actual;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(pair.load_raw().1, THREADS as u64 * INCREMENTS);
+    }
+}
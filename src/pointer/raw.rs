@@ -5,20 +5,21 @@ use core::ptr::{self, NonNull};
 
 use typenum::{IsGreaterOrEqual, True, Unsigned};
 
+use crate::dst::Pointee;
 use crate::pointer::{self, MarkedNonNull, MarkedPtr};
 
 /********** impl Clone ****************************************************************************/
 
-impl<T, N> Clone for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N> Clone for MarkedPtr<T, N> {
     #[inline]
     fn clone(&self) -> Self {
-        Self::new(self.inner)
+        Self { inner: self.inner, _marker: PhantomData }
     }
 }
 
 /********** impl Copy *****************************************************************************/
 
-impl<T, N> Copy for MarkedPtr<T, N> {}
+impl<T: ?Sized + Pointee, N> Copy for MarkedPtr<T, N> {}
 
 /********** impl inherent (const) *****************************************************************/
 
@@ -28,34 +29,36 @@ impl<T, N> MarkedPtr<T, N> {
     pub const fn new(ptr: *mut T) -> Self {
         Self { inner: ptr, _marker: PhantomData }
     }
+}
 
+impl<T: ?Sized + Pointee, N> MarkedPtr<T, N> {
     /// Creates a new & unmarked `null` pointer.
     #[inline]
     pub const fn null() -> Self {
-        Self::new(ptr::null_mut())
+        Self { inner: ptr::null_mut(), _marker: PhantomData }
     }
 
     /// Cast to a pointer of another type.
     #[inline]
-    pub const fn cast<U>(self) -> MarkedPtr<U, N> {
-        MarkedPtr::new(self.inner as *mut U)
+    pub const fn cast<U: ?Sized + Pointee>(self) -> MarkedPtr<U, N> {
+        MarkedPtr { inner: self.inner as *mut U::Elem, _marker: PhantomData }
     }
 
     /// Creates a marked pointer from the numeric representation of a
     /// potentially marked pointer.
     #[inline]
     pub const fn from_usize(val: usize) -> Self {
-        Self::new(val as *mut _)
+        Self { inner: val as *mut _, _marker: PhantomData }
     }
 }
 
 /********** impl inherent *************************************************************************/
 
-impl<T, N: Unsigned> MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> MarkedPtr<T, N> {
     /// The number of available mark bits for this type.
     pub const MARK_BITS: usize = N::USIZE;
     /// The bitmask for the lower markable bits.
-    pub const MARK_MASK: usize = pointer::mark_mask::<T>(Self::MARK_BITS);
+    pub const MARK_MASK: usize = pointer::mark_mask::<T::Elem>(Self::MARK_BITS);
     /// The bitmask for the higher pointer bits.
     pub const POINTER_MASK: usize = !Self::MARK_MASK;
 
@@ -68,18 +71,10 @@ impl<T, N: Unsigned> MarkedPtr<T, N> {
     /// Returns the inner pointer *as is*, meaning potential tags are not
     /// stripped.
     #[inline]
-    pub fn into_ptr(self) -> *mut T {
+    pub fn into_ptr(self) -> *mut T::Elem {
         self.inner
     }
 
-    /// Composes a new marked pointer from a raw unmarked pointer and a tag
-    /// value.
-    #[inline]
-    pub fn compose(ptr: *mut T, tag: usize) -> Self {
-        debug_assert_eq!(0, ptr as usize & Self::MARK_MASK, "pointer must be properly aligned");
-        Self::new(pointer::compose::<_, N>(ptr, tag))
-    }
-
     /// Converts a marked pointer with `M` potential mark bits to the **same**
     /// marked pointer with `N` potential mark bits, requires that `N >= M`.
     #[inline]
@@ -87,38 +82,56 @@ impl<T, N: Unsigned> MarkedPtr<T, N> {
     where
         N: IsGreaterOrEqual<M, Output = True>,
     {
-        Self::new(other.inner)
+        Self { inner: other.inner, _marker: PhantomData }
     }
 
     /// Clears the tag of `self` and returns the same but untagged pointer.
     #[inline]
     pub fn clear_tag(self) -> Self {
-        Self::new(self.decompose_ptr())
+        Self { inner: self.decompose_ptr(), _marker: PhantomData }
     }
 
     /// Clears the tag of `self` and replaces it with `tag`.
     #[inline]
     pub fn with_tag(self, tag: usize) -> Self {
-        Self::compose(self.decompose_ptr(), tag)
+        Self { inner: pointer::compose::<_, N>(self.decompose_ptr(), tag), _marker: PhantomData }
     }
 
     /// Decomposes the marked pointer, returning the separated raw pointer and
     /// its tag.
     #[inline]
-    pub fn decompose(self) -> (*mut T, usize) {
+    pub fn decompose(self) -> (*mut T::Elem, usize) {
         pointer::decompose(self.into_usize(), Self::MARK_BITS)
     }
 
     /// Decomposes the marked pointer, returning only the separated raw pointer.
     #[inline]
-    pub fn decompose_ptr(self) -> *mut T {
+    pub fn decompose_ptr(self) -> *mut T::Elem {
         pointer::decompose_ptr(self.into_usize(), Self::MARK_BITS)
     }
 
     /// Decomposes the marked pointer, returning only the separated tag.
     #[inline]
     pub fn decompose_tag(self) -> usize {
-        pointer::decompose_tag::<T>(self.into_usize(), Self::MARK_BITS)
+        pointer::decompose_tag::<T::Elem>(self.into_usize(), Self::MARK_BITS)
+    }
+
+    /// Returns true if the pointer is `null` (regardless of the tag).
+    #[inline]
+    pub fn is_null(self) -> bool {
+        self.decompose_ptr().is_null()
+    }
+}
+
+/********** impl inherent (sized) *****************************************************************/
+
+impl<T, N: Unsigned> MarkedPtr<T, N> {
+    /// Composes a new marked pointer from a raw unmarked pointer and a tag
+    /// value.
+    #[inline]
+    pub fn compose(ptr: *mut T, tag: usize) -> Self {
+        debug_assert_eq!(0, ptr as usize & Self::MARK_MASK, "pointer must be properly aligned");
+        Self::new(pointer::compose::<_, N>(ptr, tag))
     }
 
     /// Decomposes the marked pointer, returning an optional reference and the
@@ -184,17 +197,11 @@ impl<T, N: Unsigned> MarkedPtr<T, N> {
     pub unsafe fn as_mut<'a>(self) -> Option<&'a mut T> {
         self.decompose_ptr().as_mut()
     }
-
-    /// Returns true if the pointer is `null` (regardless of the tag).
-    #[inline]
-    pub fn is_null(self) -> bool {
-        self.decompose_ptr().is_null()
-    }
 }
 
 /********** impl Default **************************************************************************/
 
-impl<T, N: Unsigned> Default for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> Default for MarkedPtr<T, N> {
     #[inline]
     fn default() -> Self {
         Self::null()
@@ -203,7 +210,7 @@ impl<T, N: Unsigned> Default for MarkedPtr<T, N> {
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, N: Unsigned> fmt::Debug for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> fmt::Debug for MarkedPtr<T, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.decompose();
@@ -213,7 +220,7 @@ impl<T, N: Unsigned> fmt::Debug for MarkedPtr<T, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, N: Unsigned> fmt::Pointer for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N: Unsigned> fmt::Pointer for MarkedPtr<T, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.decompose_ptr(), f)
@@ -274,14 +281,14 @@ impl<T, N: Unsigned> From<(*const T, usize)> for MarkedPtr<T, N> {
 
 /********** impl PartialEq ************************************************************************/
 
-impl<T, N> PartialEq for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N> PartialEq for MarkedPtr<T, N> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.inner == other.inner
     }
 }
 
-impl<T, N> PartialEq<MarkedNonNull<T, N>> for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N> PartialEq<MarkedNonNull<T, N>> for MarkedPtr<T, N> {
     #[inline]
     fn eq(&self, other: &MarkedNonNull<T, N>) -> bool {
         self.inner.eq(&other.inner.as_ptr())
@@ -290,14 +297,14 @@ impl<T, N> PartialEq<MarkedNonNull<T, N>> for MarkedPtr<T, N> {
 
 /********** impl PartialOrd ***********************************************************************/
 
-impl<T, N> PartialOrd for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N> PartialOrd for MarkedPtr<T, N> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         self.inner.partial_cmp(&other.inner)
     }
 }
 
-impl<T, N> PartialOrd<MarkedNonNull<T, N>> for MarkedPtr<T, N> {
+impl<T: ?Sized + Pointee, N> PartialOrd<MarkedNonNull<T, N>> for MarkedPtr<T, N> {
     #[inline]
     fn partial_cmp(&self, other: &MarkedNonNull<T, N>) -> Option<cmp::Ordering> {
         self.inner.partial_cmp(&other.inner.as_ptr())
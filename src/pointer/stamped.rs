@@ -0,0 +1,441 @@
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+use typenum::Unsigned;
+
+use crate::pointer::{AtomicStampedPtr, MarkedPtr, StampedPtr};
+
+/// The number of upper address bits used for storing the stamp.
+const STAMP_BITS: usize = 16;
+/// The bit offset of the stamp within the pointer's address.
+const STAMP_SHIFT: usize = 64 - STAMP_BITS;
+/// The bitmask for the upper stamp bits.
+const STAMP_MASK: usize = !0 << STAMP_SHIFT;
+
+/// The state of [`SUPPORT`] before the platform has been probed.
+const UNKNOWN: u8 = 0;
+/// The state of [`SUPPORT`] if stamped pointers are supported.
+const SUPPORTED: u8 = 1;
+/// The state of [`SUPPORT`] if stamped pointers are not supported.
+const UNSUPPORTED: u8 = 2;
+
+/// A static value, whose address is used for probing the platform's address
+/// width.
+static PROBE: u8 = 0;
+/// The cached result of probing whether stamped pointers are supported.
+static SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/********** impl Clone ****************************************************************************/
+
+impl<T, N> Clone for StampedPtr<T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/********** impl Copy *****************************************************************************/
+
+impl<T, N> Copy for StampedPtr<T, N> {}
+
+/********** impl inherent (const) *****************************************************************/
+
+impl<T, N> StampedPtr<T, N> {
+    /// The number of available stamp bits.
+    pub const STAMP_BITS: usize = STAMP_BITS;
+    /// The bitmask for the upper stamp bits.
+    pub const STAMP_MASK: usize = STAMP_MASK;
+
+    /// Creates a new & unmarked `null` pointer with a stamp of zero.
+    #[inline]
+    pub const fn null() -> Self {
+        Self::from_usize(0)
+    }
+
+    /// Creates a stamped pointer from the numeric representation of a
+    /// potentially marked and stamped pointer.
+    #[inline]
+    pub const fn from_usize(val: usize) -> Self {
+        Self { inner: val, _marker: PhantomData }
+    }
+
+    /// Returns the numeric representation of the pointer including its tag
+    /// and stamp.
+    #[inline]
+    pub const fn into_usize(self) -> usize {
+        self.inner
+    }
+
+    /// Decomposes the stamped pointer, returning only the separated stamp.
+    #[inline]
+    pub const fn decompose_stamp(self) -> u16 {
+        (self.inner >> STAMP_SHIFT) as u16
+    }
+
+    /// Decomposes the stamped pointer, returning only the marked pointer
+    /// without its stamp.
+    #[inline]
+    pub const fn decompose_ptr(self) -> MarkedPtr<T, N> {
+        MarkedPtr::from_usize(self.inner & !STAMP_MASK)
+    }
+
+    /// Decomposes the stamped pointer, returning the separated marked pointer
+    /// and stamp.
+    #[inline]
+    pub const fn decompose(self) -> (MarkedPtr<T, N>, u16) {
+        (self.decompose_ptr(), self.decompose_stamp())
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, N: Unsigned> StampedPtr<T, N> {
+    /// Composes a stamped pointer from a (marked) pointer and a stamp.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address of `ptr` uses any of the upper bits reserved for
+    /// the stamp.
+    #[inline]
+    pub fn compose(ptr: MarkedPtr<T, N>, stamp: u16) -> Self {
+        let ptr = ptr.into_usize();
+        assert_eq!(ptr & STAMP_MASK, 0, "pointer address exceeds the available address bits");
+        Self::from_usize(ptr | (stamp as usize) << STAMP_SHIFT)
+    }
+
+    /// Returns `true` if the pointer is `null`, regardless of its tag and
+    /// stamp.
+    #[inline]
+    pub fn is_null(self) -> bool {
+        self.decompose_ptr().is_null()
+    }
+
+    /// Returns the same pointer with its stamp incremented by one, wrapping
+    /// around on overflow.
+    #[inline]
+    fn next(self, ptr: MarkedPtr<T, N>) -> Self {
+        Self::compose(ptr, self.decompose_stamp().wrapping_add(1))
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T, N> Default for StampedPtr<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, N: Unsigned> fmt::Debug for StampedPtr<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ((ptr, tag), stamp) = (self.decompose_ptr().decompose(), self.decompose_stamp());
+        f.debug_struct("StampedPtr")
+            .field("ptr", &ptr)
+            .field("tag", &tag)
+            .field("stamp", &stamp)
+            .finish()
+    }
+}
+
+/********** impl PartialEq ************************************************************************/
+
+impl<T, N> PartialEq for StampedPtr<T, N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+/********** impl Eq *******************************************************************************/
+
+impl<T, N> Eq for StampedPtr<T, N> {}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<T, N> Send for AtomicStampedPtr<T, N> {}
+unsafe impl<T, N> Sync for AtomicStampedPtr<T, N> {}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, N> AtomicStampedPtr<T, N> {
+    /// Creates a new & unmarked `null` pointer with a stamp of zero.
+    #[inline]
+    pub const fn null() -> Self {
+        Self { inner: AtomicUsize::new(0), _marker: PhantomData }
+    }
+
+    /// Returns `true` if the platform's address width leaves the upper 16
+    /// bits of all pointers unused, which is required for storing the stamp.
+    ///
+    /// This is the case for user-space addresses on `x86_64` and `aarch64`
+    /// Linux, as long as the kernel does not hand out addresses beyond the
+    /// (default) 48-bit virtual address space and heap pointers are not tagged
+    /// (e.g. by the memory tagging extension on `aarch64` Android).
+    /// The platform is only probed once and the result is cached for all
+    /// later calls.
+    #[inline]
+    pub fn is_supported() -> bool {
+        match SUPPORT.load(Ordering::Relaxed) {
+            SUPPORTED => true,
+            UNSUPPORTED => false,
+            _ => {
+                let supported = probe_address_width();
+                SUPPORT.store(if supported { SUPPORTED } else { UNSUPPORTED }, Ordering::Relaxed);
+                supported
+            }
+        }
+    }
+}
+
+impl<T, N: Unsigned> AtomicStampedPtr<T, N> {
+    /// Creates a new `AtomicStampedPtr` with a stamp of zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if stamped pointers are not [supported][AtomicStampedPtr::is_supported]
+    /// on the current platform.
+    #[inline]
+    pub fn new(ptr: MarkedPtr<T, N>) -> Self {
+        assert!(Self::is_supported(), "stamped pointers are not supported on this platform");
+        Self {
+            inner: AtomicUsize::new(StampedPtr::compose(ptr, 0).into_usize()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Consumes `self` and returns the inner [`StampedPtr`].
+    #[inline]
+    pub fn into_inner(self) -> StampedPtr<T, N> {
+        StampedPtr::from_usize(self.inner.into_inner())
+    }
+
+    /// Loads the current stamped value from the pointer.
+    ///
+    /// `load` takes an [`Ordering`][ordering] argument which describes the
+    /// memory ordering of this operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`][release] or [`AcqRel`][acq_rel].
+    ///
+    /// [ordering]: core::sync::atomic::Ordering
+    /// [release]: core::sync::atomic::Ordering::Release
+    /// [acq_rel]: core::sync::atomic::Ordering::AcqRel
+    #[inline]
+    pub fn load(&self, order: Ordering) -> StampedPtr<T, N> {
+        StampedPtr::from_usize(self.inner.load(order))
+    }
+
+    /// Stores a value into the pointer and increments the stamp.
+    ///
+    /// Since the stamp has to be incremented, this is implemented as a
+    /// *compare-and-swap* loop rather than a plain store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Acquire`][acquire] or [`AcqRel`][acq_rel] or if
+    /// the address of `ptr` exceeds the available address bits.
+    ///
+    /// [acquire]: core::sync::atomic::Ordering::Acquire
+    /// [acq_rel]: core::sync::atomic::Ordering::AcqRel
+    #[inline]
+    pub fn store(&self, ptr: MarkedPtr<T, N>, order: Ordering) {
+        assert!(order != Ordering::Acquire && order != Ordering::AcqRel);
+        self.swap(ptr, order);
+    }
+
+    /// Stores a value into the pointer, increments the stamp and returns the
+    /// previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address of `ptr` exceeds the available address bits.
+    #[inline]
+    pub fn swap(&self, ptr: MarkedPtr<T, N>, order: Ordering) -> StampedPtr<T, N> {
+        let mut current = self.load(Ordering::Relaxed);
+        loop {
+            match self.compare_exchange_weak(current, ptr, order, Ordering::Relaxed) {
+                Ok(prev) => return prev,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Stores a value into the pointer if the current value (including its
+    /// stamp) is the same as `current`, incrementing the stamp of `current`.
+    ///
+    /// On success, the previous value is returned, which is guaranteed to be
+    /// equal to `current`.
+    /// On failure, the actually loaded value is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address of `new` exceeds the available address bits.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: StampedPtr<T, N>,
+        new: MarkedPtr<T, N>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<StampedPtr<T, N>, StampedPtr<T, N>> {
+        let new = current.next(new);
+        self.inner
+            .compare_exchange(current.into_usize(), new.into_usize(), success, failure)
+            .map(StampedPtr::from_usize)
+            .map_err(StampedPtr::from_usize)
+    }
+
+    /// Stores a value into the pointer if the current value (including its
+    /// stamp) is the same as `current`, incrementing the stamp of `current`.
+    ///
+    /// Unlike [`compare_exchange`][AtomicStampedPtr::compare_exchange], this
+    /// function is allowed to spuriously fail even when the comparison
+    /// succeeds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address of `new` exceeds the available address bits.
+    #[inline]
+    pub fn compare_exchange_weak(
+        &self,
+        current: StampedPtr<T, N>,
+        new: MarkedPtr<T, N>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<StampedPtr<T, N>, StampedPtr<T, N>> {
+        let new = current.next(new);
+        self.inner
+            .compare_exchange_weak(current.into_usize(), new.into_usize(), success, failure)
+            .map(StampedPtr::from_usize)
+            .map_err(StampedPtr::from_usize)
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T, N> Default for AtomicStampedPtr<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, N: Unsigned> fmt::Debug for AtomicStampedPtr<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stamped = self.load(Ordering::SeqCst);
+        let (ptr, tag) = stamped.decompose_ptr().decompose();
+        f.debug_struct("AtomicStampedPtr")
+            .field("ptr", &ptr)
+            .field("tag", &tag)
+            .field("stamp", &stamped.decompose_stamp())
+            .finish()
+    }
+}
+
+/********** impl From *****************************************************************************/
+
+impl<T, N: Unsigned> From<MarkedPtr<T, N>> for AtomicStampedPtr<T, N> {
+    #[inline]
+    fn from(ptr: MarkedPtr<T, N>) -> Self {
+        AtomicStampedPtr::new(ptr)
+    }
+}
+
+/********** helper functions **********************************************************************/
+
+/// Returns `true` if the addresses of the static data, stack and heap segments
+/// all leave the upper stamp bits unused.
+#[cold]
+fn probe_address_width() -> bool {
+    if !cfg!(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))) {
+        return false;
+    }
+
+    let local = 0u8;
+    let heap = Box::new(0u8);
+    let addresses =
+        [&PROBE as *const u8 as usize, &local as *const u8 as usize, &*heap as *const u8 as usize];
+    addresses.iter().all(|addr| addr & STAMP_MASK == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::Ordering::Relaxed;
+
+    use typenum::U2;
+
+    use crate::align::Aligned4;
+
+    type AtomicStampedPtr<T> = crate::pointer::AtomicStampedPtr<T, U2>;
+    type MarkedPtr<T> = crate::pointer::MarkedPtr<T, U2>;
+    type StampedPtr<T> = crate::pointer::StampedPtr<T, U2>;
+
+    #[test]
+    fn compose() {
+        let mut value = Aligned4(1);
+        let marked = MarkedPtr::compose(&mut value, 0b11);
+        let stamped = StampedPtr::compose(marked, u16::MAX);
+
+        assert_eq!(stamped.decompose(), (marked, u16::MAX));
+        assert_eq!(unsafe { stamped.decompose_ptr().decompose_ref() }, (Some(&value), 0b11));
+    }
+
+    #[test]
+    #[should_panic]
+    fn compose_exceeds_address_bits() {
+        let _ = StampedPtr::<Aligned4<i32>>::compose(MarkedPtr::from_usize(1 << 48), 0);
+    }
+
+    #[test]
+    fn support_cached() {
+        let supported = AtomicStampedPtr::<Aligned4<i32>>::is_supported();
+        assert_ne!(super::SUPPORT.load(Relaxed), super::UNKNOWN);
+        assert_eq!(AtomicStampedPtr::<Aligned4<i32>>::is_supported(), supported);
+    }
+
+    #[test]
+    fn stamp_increment() {
+        if !AtomicStampedPtr::<Aligned4<i32>>::is_supported() {
+            return;
+        }
+
+        let (mut a, mut b) = (Aligned4(1), Aligned4(2));
+        let (a, b) = (MarkedPtr::new(&mut a), MarkedPtr::new(&mut b));
+        let atomic = AtomicStampedPtr::new(a);
+        let initial = atomic.load(Relaxed);
+        assert_eq!(initial.decompose(), (a, 0));
+
+        // a -> b -> a changes the stamp, so the ABA problem is detected
+        assert_eq!(atomic.swap(b, Relaxed), initial);
+        atomic.store(a, Relaxed);
+        assert_eq!(atomic.load(Relaxed).decompose(), (a, 2));
+
+        let res = atomic.compare_exchange(initial, b, Relaxed, Relaxed);
+        assert_eq!(res.unwrap_err().decompose(), (a, 2));
+
+        let current = atomic.load(Relaxed);
+        assert!(atomic.compare_exchange(current, b, Relaxed, Relaxed).is_ok());
+        assert_eq!(atomic.load(Relaxed).decompose(), (b, 3));
+    }
+
+    #[test]
+    fn stamp_wrapping() {
+        let mut value = Aligned4(1);
+        let ptr = MarkedPtr::new(&mut value);
+        let stamped = StampedPtr::compose(ptr, u16::MAX);
+
+        assert_eq!(stamped.next(ptr).decompose(), (ptr, 0));
+    }
+}
@@ -0,0 +1,209 @@
+//! The global state of the quiescent-state-based reclamation scheme, which is
+//! shared by all threads.
+
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::registry::{Entry, Registry};
+use crate::retired::Retired;
+
+use super::Qsbr;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global grace period counter, the registry of all participating threads
+/// and the records that were left behind by exiting threads.
+#[derive(Debug)]
+pub(crate) struct Global {
+    grace_period: AtomicUsize,
+    participants: Registry<Participant>,
+    abandoned: Mutex<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Creates a new global state.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            grace_period: AtomicUsize::new(1),
+            participants: Registry::new(),
+            abandoned: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Loads the current grace period.
+    #[inline]
+    pub fn current_grace_period(&self, order: Ordering) -> usize {
+        self.grace_period.load(order)
+    }
+
+    /// Registers a new participant, which is initially offline.
+    #[inline]
+    pub fn register(&self) -> &Entry<Participant> {
+        self.participants.acquire()
+    }
+
+    /// Starts a new grace period and returns the oldest grace period that
+    /// has been observed by all online participants.
+    ///
+    /// All records sealed in an earlier grace period can be safely reclaimed.
+    #[inline]
+    pub fn start_grace_period(&self) -> usize {
+        let current = self.grace_period.fetch_add(1, Ordering::SeqCst) + 1;
+        let oldest = self
+            .participants
+            .iter()
+            .filter_map(|participant| participant.observed(Ordering::Relaxed))
+            .min()
+            .unwrap_or(current);
+
+        // synchronizes with the announcements of quiescent states
+        atomic::fence(Ordering::Acquire);
+        oldest
+    }
+
+    /// Hands over all records in `records` to the global state, from where
+    /// they can eventually be reclaimed by any other thread.
+    #[inline]
+    pub fn abandon(&self, records: impl IntoIterator<Item = Sealed>) {
+        let mut abandoned = self.abandoned.lock().unwrap_or_else(|err| err.into_inner());
+        abandoned.extend(records);
+    }
+
+    /// Reclaims all abandoned records that were sealed before the `oldest`
+    /// grace period.
+    ///
+    /// If another thread is currently accessing the abandoned records, this is
+    /// a no-op.
+    #[inline]
+    pub fn reclaim_abandoned(&self, oldest: usize) {
+        let expired = match self.abandoned.try_lock() {
+            Ok(mut abandoned) => Sealed::split_expired(&mut abandoned, oldest),
+            Err(_) => return,
+        };
+
+        for sealed in expired {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Global {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline]
+    fn drop(&mut self) {
+        let abandoned = self.abandoned.get_mut().unwrap_or_else(|err| err.into_inner());
+        for sealed in abandoned.drain(..) {
+            unsafe { sealed.reclaim() };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Participant
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The globally visible state of a participating thread.
+#[derive(Debug, Default)]
+pub(crate) struct Participant {
+    /// The grace period observed in the thread's last quiescent state or zero,
+    /// if the thread is offline.
+    observed: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Participant {
+    const OFFLINE: usize = 0;
+
+    /// Announces a quiescent state, in which the thread holds no references to
+    /// any shared records.
+    #[inline]
+    pub fn announce_quiescent_state(&self, global: &Global) {
+        // all previous accesses must be completed before the announcement
+        atomic::fence(Ordering::SeqCst);
+        let grace_period = global.current_grace_period(Ordering::Relaxed);
+        self.observed.store(grace_period, Ordering::Release);
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Marks the participant as offline, so it is ignored by other threads.
+    #[inline]
+    pub fn set_offline(&self) {
+        self.observed.store(Self::OFFLINE, Ordering::Release);
+    }
+
+    /// Returns the grace period observed in the participant's last quiescent
+    /// state or `None`, if it is offline.
+    #[inline]
+    pub fn observed(&self, order: Ordering) -> Option<usize> {
+        match self.observed.load(order) {
+            Self::OFFLINE => None,
+            observed => Some(observed),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Sealed
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record sealed with the grace period at the time of its
+/// retirement.
+#[derive(Debug)]
+pub(crate) struct Sealed {
+    grace_period: usize,
+    record: Retired<Qsbr>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Sealed {
+    /// Creates a new sealed record.
+    #[inline]
+    pub fn new(record: Retired<Qsbr>, grace_period: usize) -> Self {
+        Self { grace_period, record }
+    }
+
+    /// Reclaims the sealed record.
+    ///
+    /// # Safety
+    ///
+    /// The record must be expired and must not be reclaimed more than once.
+    #[inline]
+    pub unsafe fn reclaim(mut self) {
+        self.record.reclaim();
+    }
+
+    /// Removes all records that were sealed before the `oldest` grace period
+    /// from `records` and returns them.
+    ///
+    /// Every online thread has passed through a quiescent state since these
+    /// records were retired.
+    #[inline]
+    pub fn split_expired(records: &mut Vec<Sealed>, oldest: usize) -> Vec<Sealed> {
+        let (expired, remaining) =
+            records.drain(..).partition(|sealed| sealed.grace_period < oldest);
+        *records = remaining;
+        expired
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Sealed {}
@@ -0,0 +1,194 @@
+//! The thread local state of the quiescent-state-based reclamation scheme.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::sync::atomic::{self, Ordering};
+
+use crate::registry::Entry;
+use crate::retired::Retired;
+
+use super::global::{Global, Participant, Sealed};
+use super::Qsbr;
+
+/// The number of records a thread retires before it attempts to reclaim its
+/// expired records (must be a power of two).
+const RECLAIM_THRESHOLD: usize = 128;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Local
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The thread local state of a thread participating in the [`Qsbr`]
+/// reclamation scheme.
+///
+/// Each thread implicitly creates its own instance when it first interacts
+/// with the scheme.
+/// A thread only becomes *online* once it creates its first guard or is
+/// explicitly marked as [`online`][Qsbr::online], so threads that merely
+/// retire or reclaim records never hold up any grace period.
+/// The instance of the current thread can be accessed through
+/// [`Qsbr::with_local`][super::Qsbr::with_local].
+#[derive(Debug)]
+pub struct Local {
+    global: &'static Global,
+    participant: &'static Entry<Participant>,
+    guard_count: Cell<usize>,
+    status: Cell<Status>,
+    retire_count: Cell<usize>,
+    retired: RefCell<Vec<Sealed>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Local {
+    /// Creates a new local state and registers it as (not yet online)
+    /// participant in the global state.
+    #[inline]
+    pub(crate) fn new(global: &'static Global) -> Self {
+        Self {
+            global,
+            participant: global.register(),
+            guard_count: Cell::new(0),
+            status: Cell::new(Status::Inactive),
+            retire_count: Cell::new(0),
+            retired: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns `true` if the thread is currently online.
+    #[inline]
+    pub fn is_online(&self) -> bool {
+        self.status.get() == Status::Online
+    }
+
+    /// Returns `true` if there are any live guards in the current thread.
+    #[inline]
+    pub fn is_guarded(&self) -> bool {
+        self.guard_count.get() > 0
+    }
+
+    /// Increments the guard count and marks the thread as online, if it has
+    /// not yet been online or offline before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread has been marked as offline.
+    #[inline]
+    pub(crate) fn acquire_guard(&self) {
+        if self.status.get() == Status::Inactive {
+            self.online();
+        }
+
+        assert!(self.is_online(), "guards can not be created while the thread is offline");
+        let count = self.guard_count.get();
+        self.guard_count.set(count.checked_add(1).expect("guard count overflow"));
+    }
+
+    /// Decrements the guard count.
+    #[inline]
+    pub(crate) fn release_guard(&self) {
+        self.guard_count.set(self.guard_count.get() - 1);
+    }
+
+    /// Announces a quiescent state, if the thread is online.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any live guards in the current thread.
+    #[inline]
+    pub(crate) fn quiescent_state(&self) {
+        assert!(!self.is_guarded(), "quiescent state announced while guards are alive");
+        if self.is_online() {
+            self.participant.announce_quiescent_state(self.global);
+        }
+    }
+
+    /// Marks the thread as online.
+    #[inline]
+    pub(crate) fn online(&self) {
+        if self.status.replace(Status::Online) != Status::Online {
+            self.participant.announce_quiescent_state(self.global);
+        }
+    }
+
+    /// Marks the thread as offline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any live guards in the current thread.
+    #[inline]
+    pub(crate) fn offline(&self) {
+        assert!(!self.is_guarded(), "thread set offline while guards are alive");
+        if self.status.replace(Status::Offline) == Status::Online {
+            atomic::fence(Ordering::SeqCst);
+            self.participant.set_offline();
+        }
+    }
+
+    /// Seals the given `record` with the current grace period and stores it
+    /// until it expires.
+    #[inline]
+    pub(crate) fn retire_record(&self, record: Retired<Qsbr>) {
+        // the grace period must be loaded *after* the record has been unlinked
+        atomic::fence(Ordering::SeqCst);
+        let grace_period = self.global.current_grace_period(Ordering::Relaxed);
+        self.retired.borrow_mut().push(Sealed::new(record, grace_period));
+
+        let count = self.retire_count.get().wrapping_add(1);
+        self.retire_count.set(count);
+        if count & (RECLAIM_THRESHOLD - 1) == 0 {
+            self.try_reclaim();
+        }
+    }
+
+    /// Starts a new grace period and reclaims all expired records retired by
+    /// this thread or abandoned by exited threads.
+    #[inline]
+    pub(crate) fn try_reclaim(&self) {
+        let oldest = self.global.start_grace_period();
+
+        // the records are taken out, because dropping them may retire further records
+        let expired = Sealed::split_expired(&mut self.retired.borrow_mut(), oldest);
+        for sealed in expired {
+            unsafe { sealed.reclaim() };
+        }
+
+        self.global.reclaim_abandoned(oldest);
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Local {
+    #[inline]
+    fn drop(&mut self) {
+        if self.status.replace(Status::Offline) == Status::Online {
+            self.participant.set_offline();
+        }
+
+        self.try_reclaim();
+        self.participant.release();
+
+        let retired = mem::take(self.retired.get_mut());
+        if !retired.is_empty() {
+            self.global.abandon(retired);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Status
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The participation status of a thread.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Status {
+    /// The thread has neither created a guard nor been marked as online or
+    /// offline, so it is ignored by all other threads.
+    Inactive,
+    /// The thread's quiescent states are tracked by all other threads.
+    Online,
+    /// The thread has been explicitly marked as offline and is ignored by all
+    /// other threads.
+    Offline,
+}
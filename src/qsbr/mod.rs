@@ -0,0 +1,427 @@
+//! A quiescent-state-based memory reclamation scheme (QSBR).
+//!
+//! Threads participating in this scheme have to explicitly announce
+//! *quiescent states* by calling [`Qsbr::quiescent_state`], i.e. points in
+//! their execution at which they hold no references to any shared records.
+//! A record retired by any thread is only reclaimed once every *online*
+//! thread has passed through at least one quiescent state since its
+//! retirement.
+//! Threads become online when they create their first [`Guard`], so threads
+//! that only retire or reclaim records are never waited for.
+//! Threads can also temporarily declare themselves [`offline`][Qsbr::offline]
+//! (e.g. before blocking), in which case they are ignored by other threads.
+//! This includes the retiring thread itself, which means its own records can
+//! only be reclaimed after it has announced a quiescent state or gone
+//! offline.
+//!
+//! Since protection is implicit between any two quiescent states, guards of
+//! this scheme are mere tokens.
+//! Creating and dropping them only involves thread local bookkeeping, but no
+//! atomic operations or memory fences, which makes this scheme's read path the
+//! fastest possible.
+//! In turn, a single online thread that never announces a quiescent state
+//! prevents any further reclamation.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::qsbr::{Guard, Owned, Qsbr};
+//! use reclaim::prelude::*;
+//!
+//! type Atomic<T> = reclaim::qsbr::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! // e.g. the body of a server's request loop
+//! {
+//!     let guard = Guard::new();
+//!     let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+//!     assert_eq!(*shared, 1);
+//!
+//!     if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!         unsafe { unlinked.retire() };
+//!     }
+//! }
+//!
+//! // all guards have been dropped, so no references can be held any more
+//! Qsbr::quiescent_state();
+//! Qsbr::try_reclaim();
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+mod global;
+mod local;
+
+pub use self::local::Local;
+
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use typenum::Unsigned;
+
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::retired::Retired;
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, ProtectRegion, Reclaim};
+
+use self::global::{Global, Sealed};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`Qsbr`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, Qsbr, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`Qsbr`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, Qsbr, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`Qsbr`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, Qsbr, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`Qsbr`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, Qsbr, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`Qsbr`] reclamation
+/// scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, Qsbr, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`Qsbr`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, Qsbr, N>;
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+thread_local!(static LOCAL: Local = Local::new(&GLOBAL));
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Qsbr
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A quiescent-state-based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct Qsbr;
+
+/********** impl inherent *************************************************************************/
+
+impl Qsbr {
+    /// Announces a quiescent state for the current thread, in which it holds
+    /// no references to any shared records.
+    ///
+    /// This is a no-op if the current thread is not online.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any live [`Guard`]s in the current thread.
+    #[inline]
+    pub fn quiescent_state() {
+        LOCAL.with(Local::quiescent_state);
+    }
+
+    /// Marks the current thread as online, e.g. after it has previously been
+    /// marked as [`offline`][Qsbr::offline].
+    ///
+    /// Threads are implicitly marked as online when they create their first
+    /// [`Guard`], unless they have been marked as offline before.
+    #[inline]
+    pub fn online() {
+        LOCAL.with(Local::online);
+    }
+
+    /// Marks the current thread as offline, which means it is ignored when
+    /// determining whether retired records can be reclaimed.
+    ///
+    /// This is useful before a thread blocks or otherwise becomes idle for
+    /// extended periods of time.
+    /// While offline, no guards can be created by the thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any live [`Guard`]s in the current thread.
+    #[inline]
+    pub fn offline() {
+        LOCAL.with(Local::offline);
+    }
+
+    /// Returns `true` if the current thread is online.
+    #[inline]
+    pub fn is_online() -> bool {
+        LOCAL.with(Local::is_online)
+    }
+
+    /// Calls `func` with the thread local state of the current thread, which
+    /// is required for retiring records through
+    /// [`retire_local`][Reclaim::retire_local] or
+    /// [`retire_local_batch`][Reclaim::retire_local_batch].
+    ///
+    /// This does not mark the current thread as online.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread's local storage has already been
+    /// destroyed.
+    #[inline]
+    pub fn with_local<T>(func: impl FnOnce(&Local) -> T) -> T {
+        LOCAL.with(func)
+    }
+
+    /// Converts an `unlinked` record into a type-erased [`Retired`] record.
+    #[inline]
+    unsafe fn into_retired<T, N: Unsigned>(unlinked: Unlinked<T, N>) -> Retired<Self> {
+        Retired::new_unchecked(
+            MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null(),
+        )
+    }
+
+    /// Retires the `record` in the local state of the current thread or, if
+    /// the thread local storage has already been destroyed, directly abandons
+    /// it to the global state.
+    #[inline]
+    fn retire_record(record: Retired<Self>) {
+        match LOCAL.try_with(|local| local as *const Local) {
+            Ok(local) => unsafe { (*local).retire_record(record) },
+            Err(_) => {
+                let grace_period = GLOBAL.current_grace_period(Ordering::SeqCst);
+                GLOBAL.abandon(Some(Sealed::new(record, grace_period)));
+            }
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for Qsbr {
+    type Guard = Guard;
+
+    #[inline]
+    fn try_reclaim() {
+        let _ = LOCAL.try_with(Local::try_reclaim);
+    }
+
+    /// Announces a quiescent state for the current thread, if it is online,
+    /// before attempting to reclaim records.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any live [`Guard`]s in the current thread.
+    #[inline]
+    fn try_reclaim_quiescent() {
+        let _ = LOCAL.try_with(|local| {
+            local.quiescent_state();
+            local.try_reclaim();
+        });
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(Self::into_retired(unlinked));
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for Qsbr {
+    type Local = Local;
+    type RecordHeader = ();
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(local: &Local, unlinked: Unlinked<T, N>) {
+        local.retire_record(Self::into_retired(unlinked));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`Qsbr`] reclamation
+/// scheme.
+///
+/// Guards protect all values loaded during their lifetime, which is
+/// guaranteed not to overlap with any quiescent state of the thread they are
+/// created in.
+/// Guards are bound to the thread they are created in and can not be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct Guard {
+    local: NonNull<Local>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread has been marked as offline or if its local
+    /// storage has already been destroyed.
+    #[inline]
+    pub fn new() -> Self {
+        LOCAL.with(|local| {
+            local.acquire_guard();
+            Self { local: NonNull::from(local) }
+        })
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    #[inline]
+    fn clone(&self) -> Self {
+        unsafe { self.local.as_ref().acquire_guard() };
+        Self { local: self.local }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Guard {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.local.as_ref().release_guard() };
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = Qsbr;
+
+    /// This is a no-op, since the guard protects all values as long as it
+    /// exists.
+    #[inline]
+    fn release(&mut self) {}
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        unsafe { Marked::from_marked_ptr(atomic.load_raw(order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        match atomic.load_raw(order) {
+            raw if raw == expected => Ok(unsafe { Marked::from_marked_ptr(raw) }),
+            _ => Err(NotEqualError),
+        }
+    }
+}
+
+/********** impl ProtectRegion ********************************************************************/
+
+unsafe impl ProtectRegion for Guard {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, Qsbr};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    #[test]
+    fn online_offline() {
+        assert!(!Qsbr::is_online());
+        drop(Guard::new());
+        assert!(Qsbr::is_online());
+        Qsbr::offline();
+        assert!(!Qsbr::is_online());
+        Qsbr::online();
+        assert!(Qsbr::is_online());
+    }
+
+    #[test]
+    fn reclaim_without_guard() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        // a thread that never creates a guard does not block any grace period
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+        Qsbr::try_reclaim();
+        assert!(!Qsbr::is_online());
+
+        test_util::reclaim_until(&count, 1, Qsbr::try_reclaim);
+    }
+
+    #[test]
+    fn retire_local() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        Qsbr::with_local(|local| unsafe { Qsbr::retire_local(local, unlinked) });
+        assert!(!Qsbr::is_online());
+
+        test_util::reclaim_until(&count, 1, Qsbr::try_reclaim);
+    }
+
+    #[test]
+    #[should_panic]
+    fn quiescent_state_with_guard() {
+        let _guard = Guard::new();
+        Qsbr::quiescent_state();
+    }
+
+    #[test]
+    #[should_panic]
+    fn guard_while_offline() {
+        Qsbr::offline();
+        let _guard = Guard::new();
+    }
+
+    #[test]
+    fn reclaim_after_quiescent_state() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        Qsbr::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+        drop(guard);
+
+        test_util::reclaim_until(&count, 1, || {
+            Qsbr::quiescent_state();
+            Qsbr::try_reclaim();
+        });
+    }
+}
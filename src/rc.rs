@@ -0,0 +1,590 @@
+//! A reference counting based memory reclamation scheme.
+//!
+//! The [`Header`] of every record contains an atomic reference count.
+//! Whenever a [`Guard`] protects a value, it increments the count of the
+//! value's record and validates that the value has not been changed in the
+//! meantime.
+//! Releasing the guard decrements the count again.
+//! Retired records are reclaimed as soon as their reference count drops to
+//! zero, either immediately when they are retired or when the last guard
+//! protecting them is released.
+//!
+//! Since the window between loading a value and incrementing its reference
+//! count can not be covered by the count itself, all threads in this window
+//! are tracked globally, split into two alternating generations.
+//! Records whose count drops to zero while any thread is in this window are
+//! deferred until all threads that were in the window at that time have left
+//! it, which takes at most two generation changes.
+//! New threads entering the window are always counted in the current
+//! generation, so the previous generation drains in bounded time even with a
+//! steady stream of readers, and deferred records are reclaimed by the next
+//! thread leaving the window or by [`try_reclaim`][GlobalReclaim::try_reclaim].
+//! In the absence of such contention, records are reclaimed deterministically
+//! and without delay, which is useful for large records.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::Ordering;
+//!
+//! use reclaim::prelude::*;
+//! use reclaim::rc::{Guard, Owned};
+//!
+//! type Atomic<T> = reclaim::rc::Atomic<T, reclaim::typenum::U0>;
+//!
+//! let atomic = Atomic::new(1);
+//!
+//! let mut guard = Guard::new();
+//! let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+//! assert_eq!(*shared, 1);
+//!
+//! if let Some(unlinked) = atomic.swap(Owned::new(2), Ordering::AcqRel) {
+//!     // the unlinked record is reclaimed once `guard` releases it
+//!     unsafe { unlinked.retire() };
+//! }
+//!
+//! assert_eq!(*shared, 1);
+//! guard.release();
+//! # let mut atomic = atomic;
+//! # drop(atomic.take());
+//! ```
+
+use core::mem;
+use core::ptr::NonNull;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+use std::sync::Mutex;
+
+use typenum::Unsigned;
+
+use crate::allocator::Allocation;
+use crate::lazy::Lazy;
+use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer, MarkedPtr};
+use crate::{AcquireResult, GlobalReclaim, NotEqualError, Protect, Reclaim, Record};
+
+/// An [`Atomic`][crate::Atomic] type that uses the [`RefCount`] reclamation
+/// scheme.
+pub type Atomic<T, N> = crate::Atomic<T, RefCount, N>;
+/// An [`OwningAtomic`][crate::OwningAtomic] type that uses the [`RefCount`]
+/// reclamation scheme.
+pub type OwningAtomic<T, N> = crate::OwningAtomic<T, RefCount, N>;
+/// A [`Shared`][crate::Shared] type that uses the [`RefCount`] reclamation
+/// scheme.
+pub type Shared<'g, T, N> = crate::Shared<'g, T, RefCount, N>;
+/// An [`Owned`][crate::Owned] type that uses the [`RefCount`] reclamation
+/// scheme.
+pub type Owned<T, N> = crate::Owned<T, RefCount, N>;
+/// An [`Unlinked`][crate::Unlinked] type that uses the [`RefCount`]
+/// reclamation scheme.
+pub type Unlinked<T, N> = crate::Unlinked<T, RefCount, N>;
+/// An [`Unprotected`][crate::Unprotected] type that uses the [`RefCount`]
+/// reclamation scheme.
+pub type Unprotected<T, N> = crate::Unprotected<T, RefCount, N>;
+
+/// The function for de-allocating a type-erased record.
+type Free = unsafe fn(NonNull<Header>);
+
+static GLOBAL: Lazy<Global> = Lazy::new();
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RefCount
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A reference counting based memory reclamation scheme.
+#[derive(Debug, Default)]
+pub struct RefCount;
+
+/********** impl inherent *************************************************************************/
+
+impl RefCount {
+    /// Marks the `unlinked` record as retired and reclaims it, if it is not
+    /// currently protected by any guard.
+    #[inline]
+    unsafe fn retire_record<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        let elem = MarkedNonNullable::into_marked_non_null(unlinked).decompose_non_null();
+        let header = Record::<T, Self>::header_from_raw_non_null(elem);
+        if header.state.fetch_or(Header::RETIRED, Ordering::AcqRel) == 0 {
+            GLOBAL.finalize(NonNull::from(header), free::<T>);
+        }
+    }
+}
+
+/********** impl GlobalReclaim ********************************************************************/
+
+unsafe impl GlobalReclaim for RefCount {
+    type Guard = Guard;
+
+    /// Reclaims all deferred records that can no longer be accessed by any
+    /// thread.
+    #[inline]
+    fn try_reclaim() {
+        GLOBAL.reclaim_deferred();
+    }
+
+    #[inline]
+    unsafe fn retire<T: 'static, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+
+    #[inline]
+    unsafe fn retire_unchecked<T, N: Unsigned>(unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+}
+
+/********** impl Reclaim **************************************************************************/
+
+unsafe impl Reclaim for RefCount {
+    type Local = ();
+    type RecordHeader = Header;
+
+    #[inline]
+    unsafe fn retire_local<T: 'static, N: Unsigned>(_: &(), unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+
+    #[inline]
+    unsafe fn retire_local_unchecked<T, N: Unsigned>(_: &(), unlinked: Unlinked<T, N>) {
+        Self::retire_record(unlinked);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Header
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`RecordHeader`][Reclaim::RecordHeader] type for the [`RefCount`]
+/// reclamation scheme, which contains the record's reference count.
+#[derive(Debug, Default)]
+pub struct Header {
+    state: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Header {
+    const RETIRED: usize = 0b01;
+    const CLAIMED: usize = 0b10;
+    const REF: usize = 0b100;
+
+    /// Returns the number of guards currently referencing the record.
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        self.state.load(Ordering::Relaxed) / Self::REF
+    }
+
+    /// Returns `true` if the record has been retired.
+    #[inline]
+    pub fn is_retired(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & Self::RETIRED != 0
+    }
+
+    /// Increments the reference count.
+    #[inline]
+    fn acquire(&self) {
+        self.state.fetch_add(Self::REF, Ordering::Relaxed);
+    }
+
+    /// Decrements the reference count and reclaims the record, if it has been
+    /// retired and this was the last reference to it.
+    #[inline]
+    fn release(&self, free: Free) {
+        if self.state.fetch_sub(Self::REF, Ordering::AcqRel) == Self::REF | Self::RETIRED {
+            GLOBAL.finalize(NonNull::from(self), free);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Guard
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The [`Guard`][GlobalReclaim::Guard] type for the [`RefCount`] reclamation
+/// scheme.
+///
+/// Each guard holds a reference to at most one record at a time.
+#[derive(Debug, Default)]
+pub struct Guard {
+    protected: Option<(NonNull<Header>, Free)>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Guard {
+    /// Creates a new guard, which does not yet protect any value.
+    #[inline]
+    pub fn new() -> Self {
+        Self { protected: None }
+    }
+
+    /// Loads a value from `atomic` and, if it is not null and equal to
+    /// `expected`, increments the reference count of its record until the
+    /// value has been validated.
+    ///
+    /// The previously protected record is only released once the new value
+    /// has been successfully acquired.
+    #[inline]
+    fn acquire<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: Option<MarkedPtr<T, N>>,
+        order: Ordering,
+    ) -> Result<MarkedPtr<T, N>, NotEqualError> {
+        let slot = GLOBAL.enter();
+        let res = loop {
+            let marked = atomic.load_raw(order);
+            if matches!(expected, Some(expected) if expected != marked) {
+                break Err(NotEqualError);
+            }
+
+            let ptr = match MarkedNonNull::new(marked) {
+                Marked::Value(ptr) => ptr.decompose_non_null(),
+                Marked::Null(_) => break Ok((marked, None)),
+            };
+
+            let header = unsafe { Record::<T, RefCount>::header_from_raw_non_null(ptr) };
+            header.acquire();
+
+            // the record may have been unlinked before its count was incremented
+            if atomic.load_raw(Ordering::Acquire) == marked {
+                break Ok((marked, Some((NonNull::from(header), free::<T> as Free))));
+            }
+
+            header.release(free::<T>);
+        };
+        GLOBAL.leave(slot);
+
+        res.map(|(marked, protected)| {
+            self.release();
+            self.protected = protected;
+            marked
+        })
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl Clone for Guard {
+    /// Creates a new guard, which protects the same value as `self`.
+    #[inline]
+    fn clone(&self) -> Self {
+        if let Some((header, _)) = self.protected {
+            unsafe { header.as_ref().acquire() };
+        }
+
+        Self { protected: self.protected }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl Protect for Guard {
+    type Reclaimer = RefCount;
+
+    /// Decrements the reference count of the currently protected record and
+    /// reclaims it, if it has been retired and this was the last reference to
+    /// it.
+    #[inline]
+    fn release(&mut self) {
+        if let Some((header, free)) = self.protected.take() {
+            unsafe { header.as_ref().release(free) };
+        }
+    }
+
+    #[inline]
+    fn protect<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        order: Ordering,
+    ) -> Marked<Shared<'_, T, N>> {
+        match self.acquire(atomic, None, order) {
+            Ok(marked) => unsafe { Marked::from_marked_ptr(marked) },
+            Err(_) => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn protect_if_equal<T, N: Unsigned>(
+        &mut self,
+        atomic: &Atomic<T, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> AcquireResult<'_, T, Self::Reclaimer, N> {
+        self.acquire(atomic, Some(expected), order)
+            .map(|marked| unsafe { Marked::from_marked_ptr(marked) })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Global
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The number of threads about to increment any reference count, split by
+/// generation, and the records whose reclamation had to be deferred because
+/// of them.
+#[derive(Debug, Default)]
+struct Global {
+    generation: AtomicUsize,
+    acquiring: [AtomicUsize; 2],
+    pending: AtomicUsize,
+    deferred: Mutex<Vec<Deferred>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Global {
+    /// Marks the calling thread as about to increment a reference count and
+    /// returns the slot of the generation it is counted in.
+    #[inline]
+    fn enter(&self) -> usize {
+        let slot = self.generation.load(Ordering::SeqCst) & 1;
+        self.acquiring[slot].fetch_add(1, Ordering::SeqCst);
+        atomic::fence(Ordering::SeqCst);
+        slot
+    }
+
+    /// Marks the calling thread as no longer about to increment a reference
+    /// count and reclaims all deferred records that have become safe to
+    /// reclaim.
+    #[inline]
+    fn leave(&self, slot: usize) {
+        self.acquiring[slot].fetch_sub(1, Ordering::SeqCst);
+        self.reclaim_deferred();
+    }
+
+    /// Claims and reclaims the retired and no longer referenced record with
+    /// the given `header`, unless another thread has already claimed it.
+    ///
+    /// If any thread is currently about to increment any reference count, the
+    /// reclamation is deferred.
+    #[inline]
+    fn finalize(&self, header: NonNull<Header>, free: Free) {
+        let state = unsafe { &header.as_ref().state };
+        let claimed = Header::RETIRED | Header::CLAIMED;
+        if state
+            .compare_exchange(Header::RETIRED, claimed, Ordering::AcqRel, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
+
+        atomic::fence(Ordering::SeqCst);
+        if self.acquiring.iter().all(|count| count.load(Ordering::SeqCst) == 0) {
+            unsafe { free(header) };
+            return;
+        }
+
+        {
+            let mut deferred = self.deferred.lock().unwrap_or_else(|err| err.into_inner());
+            // the generation is only advanced while holding the lock
+            let generation = self.generation.load(Ordering::Relaxed);
+            deferred.push(Deferred { header, free, generation });
+            self.pending.fetch_add(1, Ordering::Release);
+        }
+
+        self.reclaim_deferred();
+    }
+
+    /// Advances the generation as far as possible and reclaims all deferred
+    /// records that can no longer be accessed by any thread.
+    #[inline]
+    fn reclaim_deferred(&self) {
+        if self.pending.load(Ordering::Acquire) == 0 {
+            return;
+        }
+
+        // the records are taken out, because reclaiming them may retire further records
+        let expired: Vec<_> = {
+            let mut deferred = match self.deferred.try_lock() {
+                Ok(deferred) => deferred,
+                Err(_) => return,
+            };
+
+            atomic::fence(Ordering::SeqCst);
+            let mut generation = self.generation.load(Ordering::Relaxed);
+            // a new generation can only begin once no thread is counted in the slot it re-uses
+            for _ in 0..2 {
+                if self.acquiring[(generation + 1) & 1].load(Ordering::SeqCst) != 0 {
+                    break;
+                }
+
+                generation = generation.wrapping_add(1);
+                self.generation.store(generation, Ordering::SeqCst);
+            }
+
+            // any thread that could access a record deferred in generation `g` must be counted in
+            // the slots of `g` or `g + 1`, so both must have drained since then
+            let (expired, remaining) = mem::take(&mut *deferred)
+                .into_iter()
+                .partition(|record| generation.wrapping_sub(record.generation) >= 2);
+            *deferred = remaining;
+            expired
+        };
+
+        self.pending.fetch_sub(expired.len(), Ordering::Relaxed);
+        for Deferred { header, free, .. } in expired {
+            unsafe { free(header) };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Deferred
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A claimed record, whose reclamation had to be deferred.
+#[derive(Debug)]
+struct Deferred {
+    header: NonNull<Header>,
+    free: Free,
+    generation: usize,
+}
+
+/********** impl Send *****************************************************************************/
+
+// retired records can be reclaimed by any thread
+unsafe impl Send for Deferred {}
+
+/********** helper functions **********************************************************************/
+
+/// Reclaims the record of type `T` containing the given `header`.
+#[inline]
+unsafe fn free<T>(header: NonNull<Header>) {
+    let record = (header.as_ptr() as usize) - Record::<T, RefCount>::offset_header();
+    Allocation::reclaim(NonNull::new_unchecked(record as *mut Record<T, RefCount>));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use typenum::U0;
+
+    use crate::prelude::*;
+    use crate::test_util::{self, DropCount};
+
+    use super::{Guard, RefCount};
+
+    type Atomic<T> = super::Atomic<T, U0>;
+    type Owned<T> = super::Owned<T, U0>;
+
+    fn reclaim_until(count: &AtomicUsize, expected: usize) {
+        test_util::reclaim_until(count, expected, RefCount::try_reclaim);
+    }
+
+    #[test]
+    fn ref_count() {
+        let atomic = Atomic::new(1);
+        let mut guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let header = unsafe {
+            crate::Record::<_, RefCount>::header_from_raw(shared.as_marked_ptr().decompose_ptr())
+        };
+        assert_eq!(header.ref_count(), 1);
+
+        let clone = guard.clone();
+        assert_eq!(header.ref_count(), 2);
+        drop(clone);
+        guard.release();
+        assert_eq!(header.ref_count(), 0);
+
+        let mut atomic = atomic;
+        drop(atomic.take());
+    }
+
+    #[test]
+    fn reclaim_on_release() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let shared = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(Arc::strong_count(&shared.0), 2);
+
+        guard.release();
+        reclaim_until(&count, 1);
+    }
+
+    #[test]
+    fn protect_if_equal_keeps_protection() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Atomic::new(DropCount(Arc::clone(&count)));
+        let mut other = Atomic::new(DropCount(Arc::clone(&count)));
+
+        let mut guard = Guard::new();
+        let _ = atomic.load(Ordering::Acquire, &mut guard).unwrap();
+        let unlinked = atomic.swap(Owned::none(), Ordering::AcqRel).unwrap();
+        unsafe { unlinked.retire() };
+
+        // a failed protection attempt must not release the previously protected value
+        let res = other.load_if_equal(Default::default(), Ordering::Acquire, &mut guard);
+        assert!(res.is_err());
+        RefCount::try_reclaim();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        guard.release();
+        reclaim_until(&count, 1);
+
+        drop(other.take());
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn reclaim_with_steady_readers() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let atomic = Arc::new(Atomic::new(DropCount(Arc::clone(&count))));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let atomic = Arc::clone(&atomic);
+                let stop = Arc::clone(&stop);
+                thread::spawn(move || {
+                    let mut guard = Guard::new();
+                    while !stop.load(Ordering::Relaxed) {
+                        let _ = atomic.load(Ordering::Acquire, &mut guard);
+                        guard.release();
+                    }
+                })
+            })
+            .collect();
+
+        for _ in 0..100 {
+            let owned = Owned::new(DropCount(Arc::clone(&count)));
+            let unlinked = atomic.swap(owned, Ordering::AcqRel).unwrap();
+            unsafe { unlinked.retire() };
+        }
+
+        // deferred records must not have to wait for the readers to stop
+        reclaim_until(&count, 100);
+        stop.store(true, Ordering::Relaxed);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        let mut atomic = Arc::try_unwrap(atomic).unwrap();
+        drop(atomic.take());
+        assert_eq!(count.load(Ordering::Relaxed), 101);
+    }
+
+    #[test]
+    fn reclaim_concurrent() {
+        let count = test_util::retire_from_threads::<RefCount>(4, 100);
+        reclaim_until(&count, 400);
+    }
+}
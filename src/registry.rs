@@ -0,0 +1,187 @@
+//! An append-only, lock-free registry of re-usable entries, which is used by
+//! the built-in reclamation schemes for keeping track of all participating
+//! threads.
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use core::iter::FusedIterator;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{
+    AtomicBool, AtomicPtr,
+    Ordering::{Acquire, Relaxed, Release},
+};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Registry
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A concurrent linked list of entries, which can only grow but never shrink.
+///
+/// Entries are never de-allocated as long as the registry itself is alive.
+/// Instead, an entry can be released, after which it can be acquired again
+/// by another thread.
+#[derive(Debug)]
+pub(crate) struct Registry<T> {
+    head: AtomicPtr<Entry<T>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T> Registry<T> {
+    /// Creates a new empty registry.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { head: AtomicPtr::new(ptr::null_mut()) }
+    }
+
+    /// Returns an iterator over all entries in the registry, regardless of
+    /// whether they are currently acquired or not.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { curr: unsafe { self.head.load(Acquire).as_ref() } }
+    }
+}
+
+impl<T: Default> Registry<T> {
+    /// Acquires an entry for exclusive use, either by re-using a previously
+    /// released one or by allocating and inserting a new one.
+    #[inline]
+    pub fn acquire(&self) -> &Entry<T> {
+        for entry in self.iter() {
+            if entry.try_acquire() {
+                return entry;
+            }
+        }
+
+        let entry = Box::leak(Box::new(Entry {
+            elem: T::default(),
+            active: AtomicBool::new(true),
+            next: ptr::null(),
+        }));
+
+        let mut head = self.head.load(Relaxed);
+        loop {
+            entry.next = head;
+            match self.head.compare_exchange_weak(head, entry, Release, Relaxed) {
+                Ok(_) => return entry,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<T: Send + Sync> Send for Registry<T> {}
+unsafe impl<T: Send + Sync> Sync for Registry<T> {}
+
+/********** impl Drop *****************************************************************************/
+
+impl<T> Drop for Registry<T> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut curr = *self.head.get_mut();
+        while !curr.is_null() {
+            let entry = unsafe { Box::from_raw(curr) };
+            curr = entry.next as *mut _;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Entry
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An entry in a [`Registry`].
+#[derive(Debug)]
+pub(crate) struct Entry<T> {
+    elem: T,
+    active: AtomicBool,
+    next: *const Entry<T>,
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<T: Send + Sync> Send for Entry<T> {}
+unsafe impl<T: Send + Sync> Sync for Entry<T> {}
+
+/********** impl inherent *************************************************************************/
+
+impl<T> Entry<T> {
+    /// Releases the entry, so it can be acquired again by another thread.
+    ///
+    /// The entry must not be used any more by the releasing thread afterwards.
+    #[inline]
+    pub fn release(&self) {
+        self.active.store(false, Release);
+    }
+
+    #[inline]
+    fn try_acquire(&self) -> bool {
+        !self.active.load(Relaxed)
+            && self.active.compare_exchange(false, true, Acquire, Relaxed).is_ok()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T> Deref for Entry<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.elem
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Iter
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over all entries of a [`Registry`].
+#[derive(Debug)]
+pub(crate) struct Iter<'a, T> {
+    curr: Option<&'a Entry<T>>,
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a Entry<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.curr.take()?;
+        self.curr = unsafe { entry.next.as_ref() };
+        Some(entry)
+    }
+}
+
+/********** impl FusedIterator ********************************************************************/
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::Registry;
+
+    #[test]
+    fn acquire_and_reuse() {
+        let registry: Registry<AtomicUsize> = Registry::new();
+        let first = registry.acquire();
+        let second = registry.acquire();
+        assert_eq!(registry.iter().count(), 2);
+
+        first.store(1, Ordering::Relaxed);
+        first.release();
+        second.store(2, Ordering::Relaxed);
+
+        let third = registry.acquire();
+        assert_eq!(third.load(Ordering::Relaxed), 1);
+        assert_eq!(registry.iter().count(), 2);
+    }
+}
@@ -1,20 +1,32 @@
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
 use core::cmp;
 use core::fmt;
 use core::marker::PhantomData;
-use core::mem;
+use core::mem::ManuallyDrop;
 use core::ptr::NonNull;
 
-#[cfg(not(feature = "std"))]
-use alloc::boxed::Box;
-
+use crate::allocator::{self, Release};
 use crate::{Reclaim, Record};
 
+/// The tag bit marking retired records with a custom deleter.
+const CUSTOM_TAG: usize = 0b1;
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Retired
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A type-erased fat pointer to a retired record.
-pub struct Retired<R>(NonNull<dyn Any + 'static>, PhantomData<R>);
+/// A type-erased thin pointer to a retired record.
+///
+/// The function for dropping and de-allocating a record is stored in front of
+/// the record itself, so a retired record only occupies a single word.
+/// Records with a custom deleter are stored behind a separately allocated
+/// [`CustomDeleter`], which is marked by a tag bit in the pointer.
+pub struct Retired<R> {
+    ptr: NonNull<()>,
+    _marker: PhantomData<R>,
+}
 
 /********** impl inherent *************************************************************************/
 
@@ -35,30 +47,57 @@ impl<R: Reclaim + 'static> Retired<R> {
     ///   accessed by the [`drop`][Drop::drop] function.
     #[inline]
     pub unsafe fn new_unchecked<'a, T: 'a>(record: NonNull<T>) -> Self {
-        let any: NonNull<dyn Any + 'a> = Record::<T, R>::from_raw_non_null(record);
-        let any: NonNull<dyn Any + 'static> = mem::transmute(any);
+        let record = Record::<T, R>::from_raw_non_null(record);
+        debug_assert_eq!(record.as_ptr() as usize & CUSTOM_TAG, 0);
+        Self { ptr: record.cast(), _marker: PhantomData }
+    }
 
-        Self(any, PhantomData)
+    /// Creates a new [`Retired`] record from a raw pointer to its memory and
+    /// a custom `deleter` function, which is called with `ptr` once the
+    /// record is reclaimed.
+    ///
+    /// This allows retiring memory that was not allocated through an
+    /// [`Owned`][crate::Owned], e.g. from a memory pool or an arena.
+    /// Since such memory has no room for storing the `deleter`, both `ptr` and
+    /// `deleter` are stored in a small separate allocation, which is freed
+    /// when the record is reclaimed.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure, that `deleter` can be safely called with
+    /// `ptr` from any thread at any later point in time.
+    /// Reclamation schemes that protect records by their address (e.g.
+    /// hazard pointers) require `ptr` to point at the start of the record's
+    /// allocation.
+    #[inline]
+    pub unsafe fn with_deleter(ptr: NonNull<()>, deleter: unsafe fn(NonNull<()>)) -> Self {
+        let custom = Box::into_raw(Box::new(CustomDeleter { ptr, deleter }));
+        let tagged = (custom as usize | CUSTOM_TAG) as *mut ();
+        Self { ptr: NonNull::new_unchecked(tagged), _marker: PhantomData }
     }
 
     /// Converts a retired record to a raw pointer.
     ///
-    /// Since retired records are type-erased trait object (fat) pointers to
-    /// retired values that should no longer be used, only the 'address' part
-    /// of the pointer is returned, i.e. a pointer to an `()`.
+    /// Since retired records are type-erased pointers to retired values that
+    /// should no longer be used, only the 'address' part of the pointer is
+    /// returned, i.e. a pointer to an `()`.
     #[inline]
     pub fn as_ptr(&self) -> *const () {
-        self.0.as_ptr() as *mut () as *const ()
+        match self.custom_deleter() {
+            Some(custom) => unsafe { custom.as_ref().ptr.as_ptr() as *const () },
+            None => self.ptr.as_ptr() as *const (),
+        }
     }
 
     /// Returns the numeric representation of the retired record's memory
     /// address.
     #[inline]
     pub fn address(&self) -> usize {
-        self.0.as_ptr() as *mut () as usize
+        self.as_ptr() as usize
     }
 
-    /// Reclaims the retired record by dropping it and de-allocating its memory.
+    /// Reclaims the retired record by calling its deleter function, which
+    /// usually drops it and de-allocates its memory.
     ///
     /// # Safety
     ///
@@ -66,7 +105,25 @@ impl<R: Reclaim + 'static> Retired<R> {
     /// thread or scope still has some reference to the record.
     #[inline]
     pub unsafe fn reclaim(&mut self) {
-        mem::drop(Box::from_raw(self.0.as_ptr()));
+        match self.custom_deleter() {
+            Some(custom) => {
+                let CustomDeleter { ptr, deleter } = *Box::from_raw(custom.as_ptr());
+                deleter(ptr);
+            }
+            None => allocator::release(self.ptr, Release::Reclaim),
+        }
+    }
+
+    /// Returns the pointer to the separately allocated [`CustomDeleter`], if
+    /// the record has been retired with a custom deleter.
+    #[inline]
+    fn custom_deleter(&self) -> Option<NonNull<CustomDeleter>> {
+        match self.ptr.as_ptr() as usize {
+            tagged if tagged & CUSTOM_TAG != 0 => {
+                NonNull::new((tagged & !CUSTOM_TAG) as *mut CustomDeleter)
+            }
+            _ => None,
+        }
     }
 }
 
@@ -120,8 +177,73 @@ impl<R: Reclaim + 'static> fmt::Display for Retired<R> {
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-// Any (trait)
+// CustomDeleter
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-trait Any {}
-impl<T> Any for T {}
+/// The separately allocated pointer to a record retired with a custom
+/// deleter function.
+struct CustomDeleter {
+    ptr: NonNull<()>,
+    deleter: unsafe fn(NonNull<()>),
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Deferred
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A closure, which is executed when the record containing it is reclaimed.
+pub(crate) struct Deferred<F: FnOnce()>(ManuallyDrop<F>);
+
+/********** impl inherent *************************************************************************/
+
+impl<F: FnOnce()> Deferred<F> {
+    /// Creates a new [`Deferred`] closure.
+    #[inline]
+    pub fn new(func: F) -> Self {
+        Self(ManuallyDrop::new(func))
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<F: FnOnce()> Drop for Deferred<F> {
+    #[inline]
+    fn drop(&mut self) {
+        let func = unsafe { ManuallyDrop::take(&mut self.0) };
+        func();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem;
+    use core::ptr::NonNull;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::leak::Leaking;
+
+    use super::Retired;
+
+    #[test]
+    fn thin_pointer() {
+        assert_eq!(mem::size_of::<Retired<Leaking>>(), mem::size_of::<usize>());
+        assert_eq!(mem::size_of::<Option<Retired<Leaking>>>(), mem::size_of::<usize>());
+    }
+
+    static DELETED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe fn delete(ptr: NonNull<()>) {
+        DELETED.fetch_add(*ptr.cast::<usize>().as_ref(), Ordering::Relaxed);
+    }
+
+    #[test]
+    fn custom_deleter() {
+        let mut value = 3usize;
+        let ptr = NonNull::from(&mut value).cast();
+        let mut retired = unsafe { Retired::<Leaking>::with_deleter(ptr, delete) };
+        assert_eq!(retired.as_ptr(), ptr.as_ptr() as *const ());
+
+        unsafe { retired.reclaim() };
+        assert_eq!(DELETED.load(Ordering::Relaxed), 3);
+    }
+}
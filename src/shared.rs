@@ -8,11 +8,11 @@ use typenum::Unsigned;
 
 use crate::internal::Internal;
 use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer};
-use crate::{Reclaim, Shared, Unprotected};
+use crate::{Pointee, Reclaim, Shared, Unprotected};
 
 /********** impl Clone ****************************************************************************/
 
-impl<'g, T, R, N> Clone for Shared<'g, T, N, R> {
+impl<'g, T: ?Sized + Pointee, R, N> Clone for Shared<'g, T, N, R> {
     #[inline]
     fn clone(&self) -> Self {
         Self { inner: self.inner, _marker: PhantomData }
@@ -21,25 +21,25 @@ impl<'g, T, R, N> Clone for Shared<'g, T, N, R> {
 
 /********** impl Copy *****************************************************************************/
 
-impl<'g, T, R, N> Copy for Shared<'g, T, R, N> {}
+impl<'g, T: ?Sized + Pointee, R, N> Copy for Shared<'g, T, R, N> {}
 
 /********** impl MarkedPointer ********************************************************************/
 
-impl<'g, T, R: Reclaim, N: Unsigned> MarkedPointer for Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R: Reclaim, N: Unsigned> MarkedPointer for Shared<'g, T, R, N> {
     impl_trait!(shared);
 }
 
 /********** impl inherent *************************************************************************/
 
-impl<'g, T, R: Reclaim, N: Unsigned> Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Shared<'g, T, R, N> {
     impl_inherent!(shared);
 
     /// Decomposes the marked reference, returning the reference itself and the
     /// separated tag.
     #[inline]
     pub fn decompose_ref(shared: Self) -> (&'g T, usize) {
-        let (ptr, tag) = shared.inner.decompose();
-        unsafe { (&*ptr.as_ptr(), tag) }
+        R::on_access(shared.inner.decompose_non_null());
+        unsafe { shared.inner.decompose_ref_unbounded() }
     }
 
     /// Consumes and decomposes the marked reference, returning only the
@@ -71,7 +71,8 @@ impl<'g, T, R: Reclaim, N: Unsigned> Shared<'g, T, R, N> {
     /// ```
     #[inline]
     pub fn into_ref(shared: Self) -> &'g T {
-        unsafe { &*shared.inner.decompose_ptr() }
+        R::on_access(shared.inner.decompose_non_null());
+        unsafe { shared.inner.as_ref_unbounded() }
     }
 
     /// Converts the `Shared` reference into an [`Unprotected`].
@@ -90,34 +91,43 @@ impl<'g, T, R: Reclaim, N: Unsigned> Shared<'g, T, R, N> {
     ///
     /// The caller has to ensure the cast is valid both in terms of type and lifetime.
     #[inline]
-    pub unsafe fn cast<'h, U>(shared: Self) -> Shared<'h, U, R, N> {
+    pub unsafe fn cast<'h, U: ?Sized + Pointee>(shared: Self) -> Shared<'h, U, R, N> {
         Shared { inner: shared.inner.cast(), _marker: PhantomData }
     }
+
+    /// Converts a `Shared` reference to the record element of `T`, which is
+    /// identical to `T` for all sized types.
+    #[inline]
+    pub(crate) fn from_elem(shared: Shared<'g, T::Elem, R, N>) -> Self {
+        Self { inner: shared.inner.cast(), _marker: PhantomData }
+    }
 }
 
 /********** impl AsRef ****************************************************************************/
 
-impl<'g, T, R: Reclaim, N: Unsigned> AsRef<T> for Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R: Reclaim, N: Unsigned> AsRef<T> for Shared<'g, T, R, N> {
     #[inline]
     fn as_ref(&self) -> &T {
+        R::on_access(self.inner.decompose_non_null());
         unsafe { self.inner.as_ref() }
     }
 }
 
 /********** impl Deref ****************************************************************************/
 
-impl<'g, T, R: Reclaim, N: Unsigned> Deref for Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Deref for Shared<'g, T, R, N> {
     type Target = T;
 
     #[inline]
     fn deref(&self) -> &Self::Target {
+        R::on_access(self.inner.decompose_non_null());
         unsafe { self.inner.as_ref() }
     }
 }
 
 /********** impl Debug ****************************************************************************/
 
-impl<'g, T, R: Reclaim, N: Unsigned> fmt::Debug for Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Debug for Shared<'g, T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.inner.decompose();
@@ -127,7 +137,7 @@ impl<'g, T, R: Reclaim, N: Unsigned> fmt::Debug for Shared<'g, T, R, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<'g, T, R: Reclaim, N: Unsigned> fmt::Pointer for Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Pointer for Shared<'g, T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.inner.decompose_ptr(), f)
@@ -136,7 +146,7 @@ impl<'g, T, R: Reclaim, N: Unsigned> fmt::Pointer for Shared<'g, T, R, N> {
 
 /********** impl NonNullable **********************************************************************/
 
-impl<'g, T, R, N: Unsigned> MarkedNonNullable for Shared<'g, T, R, N> {
+impl<'g, T: ?Sized + Pointee, R, N: Unsigned> MarkedNonNullable for Shared<'g, T, R, N> {
     type Item = T;
     type MarkBits = N;
 
@@ -148,4 +158,4 @@ impl<'g, T, R, N: Unsigned> MarkedNonNullable for Shared<'g, T, R, N> {
 
 /********** impl Internal *************************************************************************/
 
-impl<'g, T, R, N> Internal for Shared<'g, T, R, N> {}
+impl<'g, T: ?Sized + Pointee, R, N> Internal for Shared<'g, T, R, N> {}
@@ -0,0 +1,80 @@
+//! Test fixtures shared by the tests of all built-in reclamation schemes.
+
+// not every fixture is used with every combination of enabled schemes
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use typenum::U0;
+
+use crate::{Atomic, GlobalReclaim, Owned};
+
+/// A record type that increments a shared counter when it is dropped.
+#[derive(Clone)]
+pub struct DropCount(pub Arc<AtomicUsize>);
+
+impl Drop for DropCount {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Repeatedly calls `try_reclaim` until `count` has reached `expected`.
+///
+/// # Panics
+///
+/// Panics, if `count` does not reach `expected` within 1000 attempts.
+pub fn reclaim_until(count: &AtomicUsize, expected: usize, try_reclaim: impl Fn()) {
+    for _ in 0..1_000 {
+        try_reclaim();
+        if count.load(Ordering::Relaxed) == expected {
+            return;
+        }
+
+        thread::yield_now();
+    }
+
+    panic!("records were not reclaimed");
+}
+
+/// Spawns `threads` threads, which each swap `records` new records into a
+/// shared [`Atomic`] and retire the previous ones with the global state of
+/// `R`, and joins them.
+///
+/// Returns the counter of all dropped records, after the record remaining in
+/// the [`Atomic`] has been dropped as well.
+pub fn retire_from_threads<R>(threads: usize, records: usize) -> Arc<AtomicUsize>
+where
+    R: GlobalReclaim,
+{
+    let count = Arc::new(AtomicUsize::new(0));
+    let atomic: Arc<Atomic<DropCount, R, U0>> = Arc::new(Atomic::null());
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let count = Arc::clone(&count);
+            let atomic = Arc::clone(&atomic);
+            thread::spawn(move || {
+                let mut guard = R::guard();
+                for _ in 0..records {
+                    let _ = atomic.load(Ordering::Acquire, &mut guard);
+                    let owned = Owned::new(DropCount(Arc::clone(&count)));
+                    if let Some(unlinked) = atomic.swap(owned, Ordering::AcqRel) {
+                        unsafe { unlinked.retire() };
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let mut atomic = Arc::try_unwrap(atomic).unwrap();
+    drop(atomic.take());
+    count
+}
@@ -8,17 +8,17 @@ use typenum::Unsigned;
 
 use crate::internal::Internal;
 use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer};
-use crate::{GlobalReclaim, Reclaim, Unlinked, Unprotected};
+use crate::{GlobalReclaim, Pointee, Reclaim, ReclaimDomain, Unlinked, Unprotected};
 
 /********** impl MarkedPointer ********************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> MarkedPointer for Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> MarkedPointer for Unlinked<T, R, N> {
     impl_trait!(unlinked);
 }
 
 /********** impl inherent *************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Unlinked<T, R, N> {
     impl_inherent!(unlinked);
 
     /// Decomposes the marked reference, returning the reference itself and the
@@ -39,7 +39,7 @@ impl<T, R: Reclaim, N: Unsigned> Unlinked<T, R, N> {
     ///
     /// The caller has to ensure the cast is valid.
     #[inline]
-    pub unsafe fn cast<U>(unlinked: Self) -> Unlinked<U, R, N> {
+    pub unsafe fn cast<U: ?Sized + Pointee>(unlinked: Self) -> Unlinked<U, R, N> {
         Unlinked { inner: unlinked.inner.cast(), _marker: PhantomData }
     }
 
@@ -63,7 +63,7 @@ impl<T, R: Reclaim, N: Unsigned> Unlinked<T, R, N> {
     where
         T: 'static,
     {
-        R::retire_local(local, self)
+        R::retire_local(local, self.into_elem())
     }
 
     /// Retires a record by calling [`retire_local_unchecked`][retire_unchecked]
@@ -84,11 +84,52 @@ impl<T, R: Reclaim, N: Unsigned> Unlinked<T, R, N> {
     /// retiring records.
     #[inline]
     pub unsafe fn retire_local_unchecked(self, local: &R::Local) {
-        R::retire_local_unchecked(local, self)
+        R::retire_local_unchecked(local, self.into_elem())
+    }
+
+    /// Retires a record by calling [`retire`][retire] on the given reclamation
+    /// `domain`.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats as with [`ReclaimDomain::retire`][retire] apply.
+    ///
+    /// [retire]: crate::ReclaimDomain::retire
+    #[inline]
+    pub unsafe fn retire_in<D>(self, domain: &D)
+    where
+        T: 'static,
+        D: ReclaimDomain<Reclaimer = R>,
+    {
+        domain.retire(self.into_elem())
+    }
+
+    /// Retires a record by calling [`retire_unchecked`][retire_unchecked] on
+    /// the given reclamation `domain`.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats as with [`ReclaimDomain::retire_unchecked`][retire_unchecked]
+    /// apply.
+    ///
+    /// [retire_unchecked]: crate::ReclaimDomain::retire_unchecked
+    #[inline]
+    pub unsafe fn retire_in_unchecked<D>(self, domain: &D)
+    where
+        D: ReclaimDomain<Reclaimer = R>,
+    {
+        domain.retire_unchecked(self.into_elem())
+    }
+
+    /// Converts the `Unlinked` reference into a reference to the record
+    /// element of `T`, which is identical to `T` for all sized types.
+    #[inline]
+    fn into_elem(self) -> Unlinked<T::Elem, R, N> {
+        Unlinked { inner: self.inner.cast(), _marker: PhantomData }
     }
 }
 
-impl<T, R: GlobalReclaim, N: Unsigned> Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: GlobalReclaim, N: Unsigned> Unlinked<T, R, N> {
     /// Retires a record by calling [`retire`][retire] on the generic
     /// reclamation parameter `R`.
     ///
@@ -109,7 +150,7 @@ impl<T, R: GlobalReclaim, N: Unsigned> Unlinked<T, R, N> {
     where
         T: 'static,
     {
-        R::retire(self)
+        R::retire(self.into_elem())
     }
 
     /// Retires a record by calling [`retire_unchecked`][retire_unchecked] on
@@ -130,13 +171,13 @@ impl<T, R: GlobalReclaim, N: Unsigned> Unlinked<T, R, N> {
     /// retiring records.
     #[inline]
     pub unsafe fn retire_unchecked(self) {
-        R::retire_unchecked(self)
+        R::retire_unchecked(self.into_elem())
     }
 }
 
 /********** impl AsRef ****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> AsRef<T> for Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> AsRef<T> for Unlinked<T, R, N> {
     #[inline]
     fn as_ref(&self) -> &T {
         unsafe { self.inner.as_ref() }
@@ -145,7 +186,7 @@ impl<T, R: Reclaim, N: Unsigned> AsRef<T> for Unlinked<T, R, N> {
 
 /********** impl Deref ****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Deref for Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Deref for Unlinked<T, R, N> {
     type Target = T;
 
     #[inline]
@@ -156,7 +197,7 @@ impl<T, R: Reclaim, N: Unsigned> Deref for Unlinked<T, R, N> {
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Debug for Unlinked<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.inner.decompose();
@@ -166,7 +207,7 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Unlinked<T, R, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Pointer for Unlinked<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.inner.decompose_ptr(), f)
@@ -175,7 +216,7 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Unlinked<T, R, N> {
 
 /********** impl NonNullable **********************************************************************/
 
-impl<T, R, N: Unsigned> MarkedNonNullable for Unlinked<T, R, N> {
+impl<T: ?Sized + Pointee, R, N: Unsigned> MarkedNonNullable for Unlinked<T, R, N> {
     type Item = T;
     type MarkBits = N;
 
@@ -187,4 +228,4 @@ impl<T, R, N: Unsigned> MarkedNonNullable for Unlinked<T, R, N> {
 
 /********** impl Internal *************************************************************************/
 
-impl<T, R, N> Internal for Unlinked<T, R, N> {}
+impl<T: ?Sized + Pointee, R, N> Internal for Unlinked<T, R, N> {}
@@ -5,11 +5,11 @@ use typenum::Unsigned;
 
 use crate::internal::Internal;
 use crate::pointer::{Marked, MarkedNonNull, MarkedNonNullable, MarkedPointer};
-use crate::{Reclaim, Shared, Unprotected};
+use crate::{Pointee, Reclaim, Shared, Unprotected};
 
 /********** impl Clone ****************************************************************************/
 
-impl<T, R, N> Clone for Unprotected<T, R, N> {
+impl<T: ?Sized + Pointee, R, N> Clone for Unprotected<T, R, N> {
     #[inline]
     fn clone(&self) -> Self {
         Self { inner: self.inner, _marker: PhantomData }
@@ -18,17 +18,17 @@ impl<T, R, N> Clone for Unprotected<T, R, N> {
 
 /********** impl Copy *****************************************************************************/
 
-impl<T, R: Reclaim, N> Copy for Unprotected<T, R, N> {}
+impl<T: ?Sized + Pointee, R: Reclaim, N> Copy for Unprotected<T, R, N> {}
 
 /********** impl MarkedPointer ********************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> MarkedPointer for Unprotected<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> MarkedPointer for Unprotected<T, R, N> {
     impl_trait!(unprotected);
 }
 
 /********** impl inherent *************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> Unprotected<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> Unprotected<T, R, N> {
     impl_inherent!(unprotected);
 
     /// Dereferences the `Unprotected`, returning the resulting reference which
@@ -78,14 +78,14 @@ impl<T, R: Reclaim, N: Unsigned> Unprotected<T, R, N> {
 
     /// Casts the [`Unprotected`] to a reference to a different type.
     #[inline]
-    pub fn cast<U>(unprotected: Self) -> Unprotected<U, R, N> {
+    pub fn cast<U: ?Sized + Pointee>(unprotected: Self) -> Unprotected<U, R, N> {
         Unprotected { inner: unprotected.inner.cast(), _marker: PhantomData }
     }
 }
 
 /********** impl Debug ****************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Unprotected<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Debug for Unprotected<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (ptr, tag) = self.inner.decompose();
@@ -95,7 +95,7 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Debug for Unprotected<T, R, N> {
 
 /********** impl Pointer **************************************************************************/
 
-impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Unprotected<T, R, N> {
+impl<T: ?Sized + Pointee, R: Reclaim, N: Unsigned> fmt::Pointer for Unprotected<T, R, N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.inner.decompose_ptr(), f)
@@ -104,7 +104,7 @@ impl<T, R: Reclaim, N: Unsigned> fmt::Pointer for Unprotected<T, R, N> {
 
 /********** impl NonNullable **********************************************************************/
 
-impl<T, R, N: Unsigned> MarkedNonNullable for Unprotected<T, R, N> {
+impl<T: ?Sized + Pointee, R, N: Unsigned> MarkedNonNullable for Unprotected<T, R, N> {
     type Item = T;
     type MarkBits = N;
 
@@ -116,4 +116,4 @@ impl<T, R, N: Unsigned> MarkedNonNullable for Unprotected<T, R, N> {
 
 /********** impl Internal *************************************************************************/
 
-impl<T, R, N> Internal for Unprotected<T, R, N> {}
+impl<T: ?Sized + Pointee, R, N> Internal for Unprotected<T, R, N> {}